@@ -1,17 +1,128 @@
 // #FF69B4 Similarity Performance Test
-#![feature(test)]
-extern crate test;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use starweave_mvp::concepts::{ConceptVector, HnswConfig, Quantization, QuantizationConfig, SimilarityEngine};
+use starweave_mvp::embedding::{Embedder, EmbeddingGenerator};
+use ndarray::{Array1, Array2};
 
-use starweave_mvp::concepts::{SimilarityEngine};
-use ndarray::Array1;
-use test::Bencher;
-
-#[bench]
-fn bench_similarity_search(b: &mut Bencher) {
+fn bench_similarity_search(c: &mut Criterion) {
     let engine = SimilarityEngine::new();
     let test_vector = Array1::from_vec(vec![0.85, -0.15, 0.45]);
 
-    b.iter(|| {
-        engine.find_best_match(&test_vector);
+    c.bench_function("similarity_search", |b| {
+        b.iter(|| engine.find_best_match(black_box(&test_vector)))
+    });
+}
+
+fn bench_embed_and_match(c: &mut Criterion) {
+    let embedder: Box<dyn Embedder> = Box::new(EmbeddingGenerator::new().unwrap());
+    let engine = SimilarityEngine::new();
+
+    c.bench_function("embed_and_match", |b| {
+        b.iter(|| {
+            let embedding = embedder.embed(black_box("what lies beyond the stars")).unwrap();
+            engine.find_best_match(&embedding)
+        })
+    });
+}
+
+fn bench_batch_scoring(c: &mut Criterion) {
+    let engine = SimilarityEngine::new();
+    let inputs = Array2::from_shape_fn((1000, 3), |(i, j)| ((i * 3 + j) as f32).sin());
+
+    c.bench_function("match_batch_1000", |b| {
+        b.iter(|| engine.match_batch(black_box(&inputs)).unwrap())
     });
 }
+
+// Exact scan vs HNSW over 20k synthetic 64-dimensional concepts
+fn bench_indexed_search(c: &mut Criterion) {
+    let concepts: Vec<ConceptVector> = (0..20_000)
+        .map(|i| ConceptVector {
+            name: format!("concept-{i}"),
+            vector: Array1::from_shape_fn(64, |j| ((i * 64 + j) as f32 * 0.618).sin()),
+            threshold: 0.0,
+            ..ConceptVector::default()
+        })
+        .collect();
+    let exact = SimilarityEngine::from_concepts(concepts.clone());
+    let indexed = SimilarityEngine::from_concepts(concepts).with_index(HnswConfig::default());
+    let query = Array1::from_shape_fn(64, |j| (j as f32 * 0.37).cos());
+    indexed.find_best_match(&query); // Build the index outside the timed loop
+
+    c.bench_function("exact_search_20k", |b| b.iter(|| exact.find_best_match(black_box(&query))));
+    c.bench_function("hnsw_search_20k", |b| b.iter(|| indexed.find_best_match(black_box(&query))));
+}
+
+// Exact scan vs int8 and product-quantized scans over 20k concepts, with the memory
+// each layout takes and how often quantized search keeps the exact top results
+fn bench_quantized_search(c: &mut Criterion) {
+    let concepts: Vec<ConceptVector> = (0..20_000)
+        .map(|i| ConceptVector {
+            name: format!("concept-{i}"),
+            vector: Array1::from_shape_fn(64, |j| ((i * 64 + j) as f32 * 0.618).sin()),
+            threshold: 0.0,
+            ..ConceptVector::default()
+        })
+        .collect();
+    let queries = Array2::from_shape_fn((50, 64), |(i, j)| ((i * 131 + j) as f32 * 0.37).cos());
+    let query = queries.row(0).to_owned();
+    let exact = SimilarityEngine::from_concepts(concepts.clone());
+    exact.find_best_match(&query); // Build the concept matrix outside the timed loop
+    println!("exact: engine holds {} bytes", exact.memory_bytes());
+    c.bench_function("exact_search_20k_f32", |b| b.iter(|| exact.find_best_match(black_box(&query))));
+
+    // Product codes are coarser, so they get a wider exact rescoring window
+    let methods = [
+        ("int8", Quantization::Int8, 32),
+        ("pq", Quantization::Product { subspaces: 16, centroids: 256 }, 512),
+    ];
+    for (label, method, rescore) in methods {
+        let config = QuantizationConfig { method, rescore, ..QuantizationConfig::default() };
+        let engine = SimilarityEngine::from_concepts(concepts.clone()).with_quantization(config);
+        engine.find_best_match(&query); // Encode outside the timed loop
+        // Concept vectors stay for rescoring; the codes replace only the f32 matrix
+        if let Ok(Some((quantized, full))) = engine.quantized_memory() {
+            println!(
+                "{label}: engine holds {} bytes vs {} exact; codes {quantized} bytes in place of a {full} byte matrix",
+                engine.memory_bytes(),
+                exact.memory_bytes(),
+            );
+        }
+        if let Ok(recall) = engine.quantization_recall(&queries, 10) {
+            println!("{label}: recall@10 {recall:.3} after rescoring");
+        }
+        let agreement = queries.rows().into_iter()
+            .filter(|q| {
+                let q = q.to_owned();
+                engine.find_best_match(&q).map(|m| m.name) == exact.find_best_match(&q).map(|m| m.name)
+            })
+            .count();
+        println!("{label}: best match agrees with exact search on {agreement}/{} queries", queries.nrows());
+        c.bench_function(&format!("quantized_search_20k_{label}"), |b| b.iter(|| engine.find_best_match(black_box(&query))));
+    }
+}
+
+// Exact scans over 10k and 100k concepts; compare runs with and without `--features parallel`
+fn bench_exact_scan(c: &mut Criterion) {
+    for size in [10_000, 100_000] {
+        let concepts: Vec<ConceptVector> = (0..size)
+            .map(|i| ConceptVector {
+                name: format!("concept-{i}"),
+                vector: Array1::from_shape_fn(64, |j| ((i * 64 + j) as f32 * 0.618).sin()),
+                threshold: 0.0,
+                ..ConceptVector::default()
+            })
+            .collect();
+        let engine = SimilarityEngine::from_concepts(concepts);
+        let query = Array1::from_shape_fn(64, |j| (j as f32 * 0.37).cos());
+        let inputs = Array2::from_shape_fn((64, 64), |(i, j)| ((i * 131 + j) as f32 * 0.37).cos());
+        engine.find_best_match(&query); // Build the concept matrix outside the timed loop
+
+        let label = size / 1000;
+        c.bench_function(&format!("exact_scan_{label}k"), |b| b.iter(|| engine.find_best_match(black_box(&query))));
+        c.bench_function(&format!("match_batch_64x{label}k"), |b| b.iter(|| engine.match_batch(black_box(&inputs)).unwrap()));
+    }
+}
+
+criterion_group!(benches, bench_similarity_search, bench_embed_and_match, bench_batch_scoring, bench_indexed_search, bench_quantized_search, bench_exact_scan);
+criterion_main!(benches);
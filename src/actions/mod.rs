@@ -1,5 +1,6 @@
 // #00CED1 Autonomous Action System (Enhanced with Co-Creation)
-use crate::concepts::ConceptVector;
+use crate::concepts::taxonomy::PATH_SEPARATOR;
+use crate::concepts::{ConceptVector, MatchExplanation};
 use crate::agent_orchestrator::AgentOrchestrator;
 use std::collections::VecDeque;
 
@@ -21,21 +22,29 @@ impl ActionSystem {
     }
 
     pub fn trigger_action(&mut self, concept: &ConceptVector, input: &str) -> String {
+        self.trigger_path_action(&concept.name, concept, input)
+    }
+
+    // Dispatches on the root of a taxonomy path such as "Verification/Code review";
+    // the full path is kept in the log
+    pub fn trigger_path_action(&mut self, path: &str, concept: &ConceptVector, input: &str) -> String {
         self.memory.push_back(input.to_string());
+        self.orchestrator.knowledge_graph.record_episode(&concept.name, input);
 
-        let action = match concept.name.as_str() {
+        let root = path.split(PATH_SEPARATOR).next().unwrap_or(path);
+        let action = match root {
             "Curiosity" => {
                 let response = self.curiosity_action(input);
-                self.log_action(&format!("[Curiosity] Researching: {input}"));
+                self.log_action(&format!("[{path}] Researching: {input}"));
                 response
             }
             "Aesthetics" => {
-                self.log_action(&format!("[Aesthetics] Creating: {input}"));
+                self.log_action(&format!("[{path}] Creating: {input}"));
                 self.aesthetics_action(input)
             }
             "Verification" => {
-                self.log_action(&format!("[Verification] Verifying: {input}"));
-                self.verification_action(input)
+                self.log_action(&format!("[{path}] Verifying: {input}"));
+                self.verification_action(path, input)
             }
             _ => {
                 self.log_action(&format!("[Default] Processing: {input}"));
@@ -52,6 +61,14 @@ impl ActionSystem {
         }
     }
 
+    // Responds to an input no concept matched; the graph only gains the input itself
+    pub fn trigger_default_action(&mut self, input: &str) -> String {
+        self.memory.push_back(input.to_string());
+        self.orchestrator.knowledge_graph.record_input(input);
+        self.log_action(&format!("[Default] Processing: {input}"));
+        "Standard response generated.".to_string()
+    }
+
     fn curiosity_action(&self, input: &str) -> String {
         format!(
             "🔍 Curiosity matched (score: {:.2}). Researching deeper aspects of: {input}",
@@ -63,8 +80,11 @@ impl ActionSystem {
         format!("🎨 Aesthetics matched. Considering artistic interpretations for: {input}")
     }
 
-    fn verification_action(&self, input: &str) -> String {
-        format!("🔬 Verification matched. Cross-referencing facts about: {input}")
+    fn verification_action(&self, path: &str, input: &str) -> String {
+        match path.split_once(PATH_SEPARATOR) {
+            Some((_, refinement)) => format!("🔬 Verification ({refinement}) matched. Cross-referencing facts about: {input}"),
+            None => format!("🔬 Verification matched. Cross-referencing facts about: {input}"),
+        }
     }
 
     // Calculate dynamic curiosity boost based on input
@@ -82,6 +102,11 @@ impl ActionSystem {
         self.action_log.push_back(action.to_string());
     }
 
+    // Keep the scores behind a decision alongside the actions it triggered
+    pub fn record_explanation(&mut self, input: &str, explanation: &MatchExplanation) {
+        self.log_action(&format!("[Match] '{input}': {}", explanation.summary()));
+    }
+
     // Get recent actions for reflection
     pub fn get_recent_actions(&self) -> Vec<String> {
         self.action_log.iter().cloned().collect()
@@ -1,6 +1,7 @@
 // #FFA07A Agent Orchestrator
 use crate::module_agent::ModuleAgent;
 use crate::concepts::cosine_similarity;
+use crate::knowledge_graph::KnowledgeGraph;
 use ndarray::Array1;
 use std::collections::HashMap;
 
@@ -8,6 +9,7 @@ pub struct AgentOrchestrator {
     pub modules: HashMap<String, ModuleAgent>,
     pub propensity_to_co_create: f32,
     pub proactive_prompts: Vec<String>,
+    pub knowledge_graph: KnowledgeGraph, // Grows with every action and co-creation
 }
 
 impl AgentOrchestrator {
@@ -22,6 +24,7 @@ impl AgentOrchestrator {
             modules: HashMap::new(),
             propensity_to_co_create: 0.3,
             proactive_prompts,
+            knowledge_graph: KnowledgeGraph::new(),
         }
     }
 
@@ -76,6 +79,8 @@ impl AgentOrchestrator {
         if !suggestions.is_empty() {
             for (name, suggestion) in &suggestions {
                 result.push_str(&format!("💡 Module '{name}' suggests: {suggestion}\n"));
+                self.knowledge_graph.record_suggestion(suggestion, name);
+                self.knowledge_graph.record_co_creation(primary_module, name);
 
                 if let Some(module) = self.modules.get_mut(name) {
                     module.record_co_creation();
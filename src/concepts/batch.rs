@@ -0,0 +1,109 @@
+// #FF69B4 Batch Similarity Scoring
+use super::{SimilarityEngine, SimilarityError};
+use crate::embedding::projection::normalize_rows;
+use ndarray::{Array2, Axis};
+use std::sync::Arc;
+
+// Best concept for one row of a batch
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BatchMatch {
+    pub concept: usize, // Index into the engine's concepts
+    pub similarity: f32,
+}
+
+impl SimilarityEngine {
+    // Similarity of every input row against every concept, with shape (inputs, concepts).
+    // Single-vector cosine engines scan the pre-normalized concept matrix; anything else is scored cell by cell.
+    pub fn score_batch(&self, inputs: &Array2<f32>) -> Result<Array2<f32>, SimilarityError> {
+        if inputs.nrows() > 0 {
+            self.check_dimension(inputs.ncols(), "batch query")?;
+        }
+        if self.concepts.is_empty() {
+            return Ok(Array2::zeros((inputs.nrows(), 0))); // No matrix to scan, as for single queries
+        }
+        if !self.matrix_compatible() {
+            let mut scores = Array2::zeros((inputs.nrows(), self.concepts.len()));
+            for (input, mut row) in inputs.rows().into_iter().zip(scores.rows_mut()) {
+                let input = input.to_owned();
+                for (cell, concept) in row.iter_mut().zip(&self.concepts) {
+                    *cell = self.score(concept, &input)?;
+                }
+            }
+            return Ok(scores);
+        }
+        self.scan_scores_batch(inputs)
+    }
+
+    // Per-row best match above each concept's threshold, mirroring `find_best_match`
+    pub fn match_batch(&self, inputs: &Array2<f32>) -> Result<Vec<Option<BatchMatch>>, SimilarityError> {
+        let scores = self.score_batch(inputs)?;
+        let mut matches = Vec::with_capacity(inputs.nrows());
+        for (input, row) in inputs.axis_iter(Axis(0)).zip(scores.axis_iter(Axis(0))) {
+            let thresholds = self.draw_thresholds();
+            let best = self.best_candidate(&input.to_owned(), row.iter().copied().enumerate(), &thresholds)?;
+            matches.push(best.map(|(concept, similarity)| BatchMatch { concept, similarity }));
+        }
+        Ok(matches)
+    }
+
+    // Embeds all texts with the attached backend and matches them in one pass
+    pub fn match_texts(&self, texts: &[&str]) -> anyhow::Result<Vec<Option<BatchMatch>>> {
+        let embedder = self.embedder()
+            .ok_or_else(|| anyhow::anyhow!("no embedder attached to the similarity engine"))?;
+        let inputs = embedder.embed_batch(texts)?;
+        Ok(self.match_batch(&inputs)?)
+    }
+
+    // Drops the cached concept matrix, index and quantized codes so the next search rebuilds them
+    pub fn refresh_concept_matrix(&self) {
+        *self.concept_matrix.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+        *self.index.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+        self.quantized_stale();
+    }
+
+    // Drops the cached matrix after one concept changed; the index is kept in step separately
+    pub(crate) fn invalidate_matrix(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+        *self.concept_matrix.get_mut().unwrap_or_else(|p| p.into_inner()) = None;
+        self.quantized_stale();
+    }
+
+    // Row-normalized concept vectors, rebuilt lazily when missing or from an older generation.
+    // Not kept while quantized codes stand in for it.
+    pub(crate) fn concept_matrix(&self) -> Result<Arc<Array2<f32>>, SimilarityError> {
+        if let Some((generation, matrix)) = self.concept_matrix.read().unwrap_or_else(|p| p.into_inner()).as_ref() {
+            if *generation == self.generation {
+                return Ok(matrix.clone());
+            }
+        }
+
+        let dimension = self.dimension().unwrap_or(0);
+        let mut matrix = Array2::zeros((self.concepts.len(), dimension));
+        for (mut row, concept) in matrix.rows_mut().into_iter().zip(&self.concepts) {
+            if concept.vector.len() != dimension {
+                return Err(SimilarityError::mismatch(
+                    dimension, concept.vector.len(), format!("concept '{}'", concept.name)
+                ));
+            }
+            row.assign(&concept.vector);
+        }
+        normalize_rows(&mut matrix);
+
+        let matrix = Arc::new(matrix);
+        if !self.uses_quantization() {
+            *self.concept_matrix.write().unwrap_or_else(|p| p.into_inner()) = Some((self.generation, matrix.clone()));
+        }
+        Ok(matrix)
+    }
+
+    // Bytes of vector data the engine holds: concept vectors and prototypes plus the
+    // cached matrix, quantized codes and index graph, whichever currently exist
+    pub fn memory_bytes(&self) -> usize {
+        let vectors: usize = self.concepts.iter().chain(&self.retired)
+            .map(|c| c.vector.len() + c.prototypes.iter().map(|p| p.len()).sum::<usize>())
+            .sum();
+        let matrix = self.concept_matrix.read().unwrap_or_else(|p| p.into_inner()).as_ref().map_or(0, |(_, m)| m.len());
+        let index = self.index.read().unwrap_or_else(|p| p.into_inner()).as_ref().map_or(0, |i| i.memory_bytes());
+        (vectors + matrix) * size_of::<f32>() + self.quantized_memory_bytes() + index
+    }
+}
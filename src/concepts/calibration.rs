@@ -0,0 +1,280 @@
+// #FF69B4 Threshold Calibration
+use super::{ConceptPack, SimilarityEngine, SimilarityError};
+use anyhow::{anyhow, bail, Context, Result};
+use ndarray::Array2;
+use serde::Deserialize;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+// Gap left below the lowest accepted score when nothing lower was observed
+const EDGE_MARGIN: f32 = 0.01;
+
+// One line of a labelled dataset; `expected` is `None` for inputs that should match nothing
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabelledExample {
+    pub text: String,
+    pub expected: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct JsonExample {
+    text: String,
+    #[serde(default, alias = "expected")]
+    concept: Option<String>,
+}
+
+// What the per-concept threshold sweep optimizes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CalibrationObjective {
+    MaxF1,
+    TargetPrecision(f32), // Highest recall whose precision reaches the target
+}
+
+// Precision / recall / F1 of one concept treated as a yes-no classifier
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ClassifierMetrics {
+    pub precision: f32,
+    pub recall: f32,
+    pub f1: f32,
+}
+
+impl ClassifierMetrics {
+    fn from_counts(true_positives: usize, false_positives: usize, positives: usize) -> Self {
+        let predicted = true_positives + false_positives;
+        let precision = if predicted == 0 { 0.0 } else { true_positives as f32 / predicted as f32 };
+        let recall = if positives == 0 { 0.0 } else { true_positives as f32 / positives as f32 };
+        let f1 = if precision + recall == 0.0 { 0.0 } else { 2.0 * precision * recall / (precision + recall) };
+        ClassifierMetrics { precision, recall, f1 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConceptCalibration {
+    pub concept: String,
+    pub positives: usize, // Examples labelled with this concept
+    pub before: f32,
+    pub after: f32,
+    pub before_metrics: ClassifierMetrics,
+    pub after_metrics: ClassifierMetrics,
+    pub met_target: bool, // Always true for MaxF1; false when a precision target was unreachable
+}
+
+// Before/after summary of a calibration run
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalibrationReport {
+    pub objective: CalibrationObjective,
+    pub examples: usize,
+    pub concepts: Vec<ConceptCalibration>, // Concepts without labelled examples are left out
+    pub accuracy_before: f32,              // Share of examples where the best match equals the label
+    pub accuracy_after: f32,
+}
+
+// Reads a labelled dataset: JSON lines `{"text": .., "concept": ..}` or `text<TAB>concept`.
+// A missing concept, an empty one or "none" means the text should not match.
+pub fn load_examples(path: impl AsRef<Path>) -> Result<Vec<LabelledExample>> {
+    let path = path.as_ref();
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("failed to read labelled examples {}", path.display()))?;
+    let json = path.extension().and_then(|e| e.to_str()).is_some_and(|e| e.eq_ignore_ascii_case("jsonl"));
+
+    let mut examples = Vec::new();
+    for (number, line) in text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+        let (text, concept) = if json {
+            let record: JsonExample = serde_json::from_str(line)
+                .with_context(|| format!("{}:{}: invalid example", path.display(), number + 1))?;
+            (record.text, record.concept)
+        } else {
+            let (text, concept) = line.rsplit_once('\t')
+                .ok_or_else(|| anyhow!("{}:{}: expected `text<TAB>concept`", path.display(), number + 1))?;
+            (text.to_string(), Some(concept.to_string()))
+        };
+        let expected = concept
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty() && !c.eq_ignore_ascii_case("none"));
+        examples.push(LabelledExample { text: text.trim().to_string(), expected });
+    }
+    Ok(examples)
+}
+
+impl SimilarityEngine {
+    // Embeds the examples with the attached backend and calibrates on them
+    pub fn calibrate(&mut self, examples: &[LabelledExample], objective: CalibrationObjective) -> Result<CalibrationReport> {
+        let embedder = self.embedder()
+            .ok_or_else(|| anyhow!("no embedder attached to the similarity engine"))?;
+        let texts: Vec<&str> = examples.iter().map(|e| e.text.as_str()).collect();
+        let inputs = embedder.embed_batch(&texts)?;
+        let labels: Vec<Option<&str>> = examples.iter().map(|e| e.expected.as_deref()).collect();
+        self.calibrate_vectors(&inputs, &labels, objective)
+    }
+
+    // Sweeps each labelled concept's threshold one-vs-rest and keeps the best one.
+    // Calibrated thresholds are marked hand-set so seed re-derivation leaves them alone.
+    pub fn calibrate_vectors(
+        &mut self,
+        inputs: &Array2<f32>,
+        labels: &[Option<&str>],
+        objective: CalibrationObjective,
+    ) -> Result<CalibrationReport> {
+        if inputs.nrows() != labels.len() {
+            bail!("{} inputs but {} labels", inputs.nrows(), labels.len());
+        }
+        if let CalibrationObjective::TargetPrecision(target) = objective {
+            if !(0.0..=1.0).contains(&target) {
+                bail!("target precision {target} is not between 0 and 1");
+            }
+        }
+        if let Some(unknown) = labels.iter().flatten().find(|l| !self.concepts.iter().any(|c| c.name == **l)) {
+            return Err(SimilarityError::UnknownConcept { name: unknown.to_string() }.into());
+        }
+
+        // Calibrate the base thresholds, not one random draw of them
+        let jitter_enabled = self.jitter_enabled();
+        self.set_jitter_enabled(false);
+        let report = self.sweep_thresholds(inputs, labels, objective);
+        self.set_jitter_enabled(jitter_enabled);
+        report
+    }
+
+    fn sweep_thresholds(
+        &mut self,
+        inputs: &Array2<f32>,
+        labels: &[Option<&str>],
+        objective: CalibrationObjective,
+    ) -> Result<CalibrationReport> {
+        let scores = self.score_batch(inputs)?;
+        let accuracy_before = self.accuracy(inputs, labels)?;
+
+        let mut concepts = Vec::new();
+        for (index, column) in scores.columns().into_iter().enumerate() {
+            let name = self.concepts[index].name.clone();
+            let samples: Vec<(f32, bool)> = column.iter()
+                .zip(labels)
+                .map(|(&score, label)| (score, *label == Some(name.as_str())))
+                .collect();
+            let positives = samples.iter().filter(|(_, positive)| *positive).count();
+            if positives == 0 {
+                continue;
+            }
+
+            let before = self.concepts[index].threshold;
+            let (after, met_target) = sweep(&samples, positives, objective);
+            self.concepts[index].threshold = after;
+            self.concepts[index].auto_threshold = false;
+            self.concept_changed(index);
+            concepts.push(ConceptCalibration {
+                concept: name,
+                positives,
+                before,
+                after,
+                before_metrics: evaluate(&samples, positives, before),
+                after_metrics: evaluate(&samples, positives, after),
+                met_target,
+            });
+        }
+
+        Ok(CalibrationReport {
+            objective,
+            examples: labels.len(),
+            concepts,
+            accuracy_before,
+            accuracy_after: self.accuracy(inputs, labels)?,
+        })
+    }
+
+    fn accuracy(&self, inputs: &Array2<f32>, labels: &[Option<&str>]) -> Result<f32, SimilarityError> {
+        if labels.is_empty() {
+            return Ok(0.0);
+        }
+        let matches = self.match_batch(inputs)?;
+        let correct = matches.iter().zip(labels)
+            .filter(|(found, label)| found.map(|m| self.concepts[m.concept].name.as_str()) == **label)
+            .count();
+        Ok(correct as f32 / labels.len() as f32)
+    }
+}
+
+fn evaluate(samples: &[(f32, bool)], positives: usize, threshold: f32) -> ClassifierMetrics {
+    let accepted = samples.iter().filter(|(score, _)| *score > threshold);
+    let true_positives = accepted.clone().filter(|(_, positive)| *positive).count();
+    let false_positives = accepted.filter(|(_, positive)| !*positive).count();
+    ClassifierMetrics::from_counts(true_positives, false_positives, positives)
+}
+
+// Tries a threshold just below every distinct score, from strict to lenient
+fn sweep(samples: &[(f32, bool)], positives: usize, objective: CalibrationObjective) -> (f32, bool) {
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let mut best: Option<(f32, f32)> = None; // (threshold, objective value)
+    let (mut true_positives, mut false_positives) = (0, 0);
+    let mut i = 0;
+    while i < sorted.len() {
+        let score = sorted[i].0;
+        while i < sorted.len() && sorted[i].0 == score {
+            if sorted[i].1 { true_positives += 1 } else { false_positives += 1 }
+            i += 1;
+        }
+        // Midway to the next lower score keeps the accepted set stable under small noise
+        let threshold = sorted.get(i).map_or(score - EDGE_MARGIN, |next| (score + next.0) / 2.0);
+        let metrics = ClassifierMetrics::from_counts(true_positives, false_positives, positives);
+        let value = match objective {
+            CalibrationObjective::MaxF1 => metrics.f1,
+            CalibrationObjective::TargetPrecision(target) if metrics.precision >= target => 1.0 + metrics.recall,
+            CalibrationObjective::TargetPrecision(_) => metrics.precision,
+        };
+        // Strict inequality: ties keep the stricter threshold found first
+        if best.is_none_or(|(_, v)| value > v) {
+            best = Some((threshold, value));
+        }
+    }
+
+    let (threshold, value) = best.unwrap_or((0.0, 0.0));
+    let met = match objective {
+        CalibrationObjective::MaxF1 => true,
+        CalibrationObjective::TargetPrecision(_) => value >= 1.0,
+    };
+    (threshold, met)
+}
+
+impl CalibrationReport {
+    // Copies calibrated thresholds into matching pack entries; returns how many changed
+    pub fn apply_to_pack(&self, pack: &mut ConceptPack) -> usize {
+        let mut updated = 0;
+        for calibration in &self.concepts {
+            if let Some(definition) = pack.concepts.iter_mut().find(|d| d.name == calibration.concept) {
+                definition.threshold = Some(calibration.after);
+                definition.auto_threshold = false;
+                updated += 1;
+            }
+        }
+        updated
+    }
+}
+
+impl fmt::Display for CalibrationObjective {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalibrationObjective::MaxF1 => f.write_str("max F1"),
+            CalibrationObjective::TargetPrecision(target) => write!(f, "precision ≥ {target:.2}"),
+        }
+    }
+}
+
+impl fmt::Display for CalibrationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Calibrated on {} examples ({})", self.examples, self.objective)?;
+        writeln!(f, "  {:<16} {:>5}  {:>15}  {:>23}  {:>23}", "concept", "n", "threshold", "P / R / F1 before", "P / R / F1 after")?;
+        for c in &self.concepts {
+            writeln!(
+                f,
+                "  {:<16} {:>5}  {:>6.3} → {:<6.3}  {:.2} / {:.2} / {:.2}        {:.2} / {:.2} / {:.2}{}",
+                c.concept, c.positives, c.before, c.after,
+                c.before_metrics.precision, c.before_metrics.recall, c.before_metrics.f1,
+                c.after_metrics.precision, c.after_metrics.recall, c.after_metrics.f1,
+                if c.met_target { "" } else { "  (target not reached)" }
+            )?;
+        }
+        write!(f, "  Best-match accuracy: {:.1}% → {:.1}%", self.accuracy_before * 100.0, self.accuracy_after * 100.0)
+    }
+}
@@ -0,0 +1,309 @@
+// #FF69B4 Concept Discovery (clustering unmatched inputs)
+use super::prototypes::centroid;
+use super::{ConceptVector, SimilarityEngine, SimilarityMetric};
+use anyhow::{anyhow, Result};
+use ndarray::{Array1, Array2, Axis};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+
+// Gap kept below the weakest member when a proposal becomes a concept (as for seed texts)
+const MEMBER_MARGIN: f32 = 0.05;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClusteringMethod {
+    Agglomerative,                                  // Average linkage, merging while clusters stay above the threshold
+    KMeans { k: usize, iterations: usize, seed: u64 }, // Spherical k-means; loose clusters are dropped afterwards
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiscoveryConfig {
+    pub method: ClusteringMethod,
+    pub similarity_threshold: f32, // Cosine a cluster's members must keep, on average, to their centroid
+    pub min_cluster_size: usize,   // Smaller clusters are not dense enough to propose
+    pub capacity: usize,           // Oldest unmatched inputs are forgotten beyond this
+    pub examples: usize,           // Representative texts shown per proposal
+}
+
+impl Default for DiscoveryConfig {
+    fn default() -> Self {
+        DiscoveryConfig {
+            method: ClusteringMethod::Agglomerative,
+            similarity_threshold: 0.75,
+            min_cluster_size: 3,
+            capacity: 500,
+            examples: 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct UnmatchedInput {
+    id: u64,
+    text: String,
+    embedding: Array1<f32>, // Normalized
+}
+
+// A dense cluster of unmatched inputs awaiting a human decision
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConceptProposal {
+    pub id: u64,
+    pub vector: Array1<f32>,       // Normalized centroid of the members
+    pub examples: Vec<String>,     // Members closest to the centroid, best first
+    pub size: usize,
+    pub cohesion: f32,             // Mean member similarity to the centroid
+    pub suggested_threshold: f32,  // Cosine, just under the weakest member
+    members: Vec<u64>,
+    texts: Vec<String>,
+}
+
+// Buffers inputs nothing matched and proposes new concepts from their clusters
+#[derive(Debug, Clone, Default)]
+pub struct ConceptDiscovery {
+    pub config: DiscoveryConfig,
+    buffer: VecDeque<UnmatchedInput>,
+    proposals: Vec<ConceptProposal>,
+    rejected: Vec<Array1<f32>>, // Centroids a human turned down; similar clusters are not proposed again
+    next_input: u64,
+    next_proposal: u64,
+}
+
+impl ConceptDiscovery {
+    pub fn new(config: DiscoveryConfig) -> Self {
+        ConceptDiscovery { config, ..Self::default() }
+    }
+
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    pub fn proposals(&self) -> &[ConceptProposal] {
+        &self.proposals
+    }
+
+    pub fn proposal(&self, id: u64) -> Option<&ConceptProposal> {
+        self.proposals.iter().find(|p| p.id == id)
+    }
+
+    // Keeps an input that matched nothing; zero embeddings (failed embeds) are ignored
+    pub fn record(&mut self, text: &str, embedding: &Array1<f32>) {
+        let norm = embedding.dot(embedding).sqrt();
+        if norm == 0.0 || !norm.is_finite() {
+            return;
+        }
+        // A different dimension means the embedder changed; older inputs can't be compared
+        if self.buffer.front().is_some_and(|u| u.embedding.len() != embedding.len()) {
+            self.buffer.clear();
+            self.rejected.clear();
+        }
+        self.buffer.push_back(UnmatchedInput { id: self.next_input, text: text.to_string(), embedding: embedding / norm });
+        self.next_input += 1;
+        while self.buffer.len() > self.config.capacity {
+            self.buffer.pop_front();
+        }
+    }
+
+    // Clusters the buffer and replaces the pending proposals, densest first.
+    // A proposal keeps its id while most of its members still cluster together.
+    pub fn discover(&mut self) -> &[ConceptProposal] {
+        let inputs: Vec<UnmatchedInput> = self.buffer.iter().cloned().collect();
+        let min_size = self.config.min_cluster_size.max(1);
+        let mut proposals = Vec::new();
+        if inputs.len() >= min_size {
+            let mut rows = Array2::zeros((inputs.len(), inputs[0].embedding.len()));
+            for (mut row, input) in rows.axis_iter_mut(Axis(0)).zip(&inputs) {
+                row.assign(&input.embedding);
+            }
+            let clusters = match self.config.method {
+                ClusteringMethod::Agglomerative => agglomerative(&rows, self.config.similarity_threshold),
+                ClusteringMethod::KMeans { k, iterations, seed } => kmeans(&rows, k, iterations, seed),
+            };
+            for members in clusters.into_iter().filter(|m| m.len() >= min_size) {
+                if let Some(proposal) = self.propose(&inputs, &members) {
+                    proposals.push(proposal);
+                }
+            }
+        }
+        proposals.sort_by(|a, b| b.size.cmp(&a.size).then(b.cohesion.total_cmp(&a.cohesion)));
+
+        let previous = std::mem::take(&mut self.proposals);
+        let mut claimed = HashSet::new();
+        for proposal in &mut proposals {
+            let kept = previous.iter()
+                .filter(|p| !claimed.contains(&p.id))
+                .map(|p| (p.members.iter().filter(|m| proposal.members.contains(m)).count(), p))
+                .filter(|(overlap, p)| overlap * 2 > p.members.len())
+                .max_by_key(|(overlap, p)| (*overlap, std::cmp::Reverse(p.id)));
+            proposal.id = match kept {
+                Some((_, p)) => p.id,
+                None => {
+                    let id = self.next_proposal;
+                    self.next_proposal += 1;
+                    id
+                }
+            };
+            claimed.insert(proposal.id);
+        }
+        self.proposals = proposals;
+        &self.proposals
+    }
+
+    fn propose(&self, inputs: &[UnmatchedInput], members: &[usize]) -> Option<ConceptProposal> {
+        let embeddings: Vec<Array1<f32>> = members.iter().map(|&i| inputs[i].embedding.clone()).collect();
+        let vector = centroid(&embeddings);
+        let mut scored: Vec<(f32, usize)> = members.iter().map(|&i| (inputs[i].embedding.dot(&vector), i)).collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let cohesion = scored.iter().map(|(s, _)| s).sum::<f32>() / scored.len() as f32;
+        let threshold = self.config.similarity_threshold;
+        if cohesion < threshold || self.rejected.iter().any(|r| r.dot(&vector) >= threshold) {
+            return None;
+        }
+
+        let weakest = scored.last().map_or(0.0, |(s, _)| *s);
+        Some(ConceptProposal {
+            id: 0, // Assigned by `discover`
+            examples: scored.iter().take(self.config.examples).map(|&(_, i)| inputs[i].text.clone()).collect(),
+            size: members.len(),
+            cohesion,
+            suggested_threshold: (weakest - MEMBER_MARGIN).clamp(-1.0, 1.0),
+            members: scored.iter().map(|&(_, i)| inputs[i].id).collect(),
+            texts: scored.iter().map(|&(_, i)| inputs[i].text.clone()).collect(),
+            vector,
+        })
+    }
+
+    // Turns a proposal into a concept named `name` and adds it to the engine.
+    // Its members become seed texts, so a new embedder can re-derive it later.
+    pub fn accept(&mut self, id: u64, name: &str, engine: &mut SimilarityEngine) -> Result<ConceptVector> {
+        let index = self.proposals.iter().position(|p| p.id == id)
+            .ok_or_else(|| anyhow!("no pending proposal #{id}"))?;
+        let proposal = &self.proposals[index];
+        let concept = ConceptVector {
+            name: name.to_string(),
+            vector: proposal.vector.clone(),
+            threshold: proposal.suggested_threshold,
+            seed_texts: proposal.texts.clone(),
+            auto_threshold: true,
+            // The threshold is a cosine; keep it one under other engine metrics
+            metric: (!engine.metric().is_cosine()).then_some(SimilarityMetric::Cosine),
+            ..ConceptVector::default()
+        };
+        let id = engine.add_concept(concept)?;
+
+        let proposal = self.proposals.remove(index);
+        self.forget(&proposal.members);
+        Ok(engine.concept_by_id(id).cloned().expect("concept was just added"))
+    }
+
+    // Drops a proposal and its inputs; returns false for an unknown id
+    pub fn reject(&mut self, id: u64) -> bool {
+        let Some(index) = self.proposals.iter().position(|p| p.id == id) else {
+            return false;
+        };
+        let proposal = self.proposals.remove(index);
+        self.forget(&proposal.members);
+        self.rejected.push(proposal.vector);
+        true
+    }
+
+    fn forget(&mut self, members: &[u64]) {
+        self.buffer.retain(|u| !members.contains(&u.id));
+    }
+}
+
+// Average-linkage clustering of normalized rows; merging stops once the closest
+// pair of clusters is less similar than `threshold`
+fn agglomerative(rows: &Array2<f32>, threshold: f32) -> Vec<Vec<usize>> {
+    let n = rows.nrows();
+    let mut similarity = rows.dot(&rows.t());
+    let mut clusters: Vec<Option<Vec<usize>>> = (0..n).map(|i| Some(vec![i])).collect();
+
+    loop {
+        let mut best: Option<(usize, usize, f32)> = None;
+        for i in (0..n).filter(|&i| clusters[i].is_some()) {
+            for j in (i + 1..n).filter(|&j| clusters[j].is_some()) {
+                if best.is_none_or(|(_, _, s)| similarity[[i, j]] > s) {
+                    best = Some((i, j, similarity[[i, j]]));
+                }
+            }
+        }
+        let Some((i, j, _)) = best.filter(|&(_, _, s)| s >= threshold) else {
+            break;
+        };
+
+        // Lance-Williams update: the merged cluster's similarity is the size-weighted mean
+        let merged = clusters[j].take().unwrap_or_default();
+        let (size_i, size_j) = (clusters[i].as_ref().map_or(0, Vec::len) as f32, merged.len() as f32);
+        for k in (0..n).filter(|&k| k != i && clusters[k].is_some()) {
+            let value = (size_i * similarity[[i, k]] + size_j * similarity[[j, k]]) / (size_i + size_j);
+            similarity[[i, k]] = value;
+            similarity[[k, i]] = value;
+        }
+        if let Some(cluster) = clusters[i].as_mut() {
+            cluster.extend(merged);
+        }
+    }
+    clusters.into_iter().flatten().collect()
+}
+
+// Spherical k-means with k-means++ seeding on normalized rows
+pub(crate) fn kmeans(rows: &Array2<f32>, k: usize, iterations: usize, seed: u64) -> Vec<Vec<usize>> {
+    let n = rows.nrows();
+    let k = k.clamp(1, n);
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut centres = vec![rows.row(rng.gen_range(0..n)).to_owned()];
+    while centres.len() < k {
+        let distances: Vec<f32> = rows.rows().into_iter()
+            .map(|row| centres.iter().map(|c| 1.0 - row.dot(c)).fold(f32::INFINITY, f32::min).max(0.0))
+            .collect();
+        let total: f32 = distances.iter().sum();
+        if total <= 0.0 {
+            break; // Fewer distinct points than k
+        }
+        let mut target = rng.gen_range(0.0..total);
+        let next = distances.iter().position(|&d| {
+            target -= d;
+            target < 0.0
+        });
+        centres.push(rows.row(next.unwrap_or(n - 1)).to_owned());
+    }
+
+    let mut assignment = vec![usize::MAX; n];
+    for _ in 0..iterations.max(1) {
+        let mut changed = false;
+        for (i, row) in rows.rows().into_iter().enumerate() {
+            let nearest = centres.iter().enumerate()
+                .max_by(|a, b| row.dot(a.1).total_cmp(&row.dot(b.1)))
+                .map_or(0, |(c, _)| c);
+            changed |= assignment[i] != nearest;
+            assignment[i] = nearest;
+        }
+        if !changed {
+            break;
+        }
+        for (c, centre) in centres.iter_mut().enumerate() {
+            let members: Vec<Array1<f32>> = (0..n).filter(|&i| assignment[i] == c).map(|i| rows.row(i).to_owned()).collect();
+            if !members.is_empty() {
+                *centre = centroid(&members);
+            }
+        }
+    }
+
+    (0..centres.len())
+        .map(|c| (0..n).filter(|&i| assignment[i] == c).collect::<Vec<_>>())
+        .filter(|members| !members.is_empty())
+        .collect()
+}
+
+impl fmt::Display for ConceptProposal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{} — {} inputs, cohesion {:.2}, threshold {:.2}", self.id, self.size, self.cohesion, self.suggested_threshold)?;
+        for example in &self.examples {
+            write!(f, "\n    “{example}”")?;
+        }
+        Ok(())
+    }
+}
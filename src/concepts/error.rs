@@ -0,0 +1,76 @@
+// #FF69B4 Similarity Pipeline Errors
+use std::fmt;
+
+// Typed failures raised instead of panicking inside ndarray
+#[derive(Debug, Clone, PartialEq)]
+pub enum SimilarityError {
+    DimensionMismatch {
+        expected: usize,
+        found: usize,
+        context: String,
+    },
+    EmptyVector {
+        context: String,
+    },
+    NoEmbedder,
+    UnknownConcept {
+        name: String,
+    },
+    DuplicateConcept {
+        name: String,
+    },
+    InvalidTaxonomy {
+        concept: String,
+        reason: String,
+    },
+    InvalidOperation {
+        concept: String,
+        reason: String,
+    },
+    InvalidExclusion {
+        concept: String,
+        reason: String,
+    },
+    Embedding {
+        model: String,
+        message: String,
+    },
+    InvalidConfig {
+        setting: String,
+        reason: String,
+    },
+}
+
+impl SimilarityError {
+    pub(crate) fn mismatch(expected: usize, found: usize, context: impl Into<String>) -> Self {
+        SimilarityError::DimensionMismatch { expected, found, context: context.into() }
+    }
+
+    pub(crate) fn embedding(model: &str, error: impl fmt::Display) -> Self {
+        SimilarityError::Embedding { model: model.to_string(), message: error.to_string() }
+    }
+}
+
+impl fmt::Display for SimilarityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimilarityError::DimensionMismatch { expected, found, context } => write!(
+                f,
+                "dimension mismatch in {context}: expected {expected}, found {found}"
+            ),
+            SimilarityError::EmptyVector { context } => write!(f, "empty vector in {context}"),
+            SimilarityError::NoEmbedder => write!(f, "no embedder attached to the similarity engine"),
+            SimilarityError::UnknownConcept { name } => write!(f, "no concept named '{name}'"),
+            SimilarityError::DuplicateConcept { name } => write!(f, "a concept named '{name}' already exists"),
+            SimilarityError::InvalidTaxonomy { concept, reason } => write!(f, "concept '{concept}': {reason}"),
+            SimilarityError::InvalidOperation { concept, reason } => write!(f, "cannot change concept '{concept}': {reason}"),
+            SimilarityError::InvalidExclusion { concept, reason } => write!(f, "concept '{concept}': {reason}"),
+            SimilarityError::Embedding { model, message } => {
+                write!(f, "embedder '{model}' failed: {message}")
+            }
+            SimilarityError::InvalidConfig { setting, reason } => write!(f, "invalid {setting}: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for SimilarityError {}
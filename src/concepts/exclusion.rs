@@ -0,0 +1,299 @@
+// #FF69B4 Negative Concepts & Exclusion Rules
+use super::{ConceptEvent, ConceptVector, SimilarityEngine, SimilarityError};
+use ndarray::Array1;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+
+// Margin used when an exclusion is created without one
+pub const DEFAULT_VETO_MARGIN: f32 = 0.8;
+
+// What happens to a concept's match once a veto fires
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum VetoAction {
+    #[default]
+    Suppress,      // The concept cannot match this input
+    Penalize(f32), // Its similarity drops by this much before the threshold check
+}
+
+// "Match this concept unless…": negative exemplars and veto concepts that rule a match out.
+// A veto fires when the input is more similar than `margin` to any of them.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Exclusion {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub negatives: Vec<Array1<f32>>,      // Hand-authored vectors, compared with the concept's own metric
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub texts: Vec<String>,               // Negative phrases; re-embedded when the embedder changes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub text_negatives: Vec<Array1<f32>>, // Embeddings of `texts`, in the same order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub vetoes: Vec<String>,              // Concept names, compared with their raw similarity
+    pub margin: f32,                 // In the units of whatever is compared
+    #[serde(default)]
+    pub action: VetoAction,
+}
+
+impl Default for Exclusion {
+    fn default() -> Self {
+        Exclusion {
+            negatives: Vec::new(),
+            texts: Vec::new(),
+            text_negatives: Vec::new(),
+            vetoes: Vec::new(),
+            margin: DEFAULT_VETO_MARGIN,
+            action: VetoAction::default(),
+        }
+    }
+}
+
+impl Exclusion {
+    pub fn is_empty(&self) -> bool {
+        self.negatives.is_empty() && self.texts.is_empty() && self.vetoes.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VetoSource {
+    Negative(usize),  // Index into the exclusion's negatives
+    Text(String),     // One of the exclusion's negative phrases
+    Concept(String),
+}
+
+// A fired veto, kept on ranked results so suppressed matches can be audited
+#[derive(Debug, Clone, PartialEq)]
+pub struct Veto {
+    pub source: VetoSource,
+    pub similarity: f32, // Input similarity to the source
+    pub margin: f32,
+    pub action: VetoAction,
+}
+
+impl Veto {
+    // The similarity left after the veto, or `None` when the match is suppressed
+    pub fn apply(&self, similarity: f32) -> Option<f32> {
+        match self.action {
+            VetoAction::Suppress => None,
+            VetoAction::Penalize(penalty) => Some(similarity - penalty),
+        }
+    }
+
+    pub fn suppresses(&self) -> bool {
+        self.action == VetoAction::Suppress
+    }
+}
+
+impl SimilarityEngine {
+    // The strongest veto against `concept` for this input, if any exceeds the margin.
+    // Negatives of another size than the input (left over from an earlier embedder) are skipped.
+    pub fn veto(&self, concept: &ConceptVector, input_vec: &Array1<f32>) -> Result<Option<Veto>, SimilarityError> {
+        let Some(exclusion) = &concept.exclusion else {
+            return Ok(None);
+        };
+        let metric = self.metric_for(concept);
+        let mut strongest: Option<(VetoSource, f32)> = None;
+        let negatives = exclusion.negatives.iter().enumerate().map(|(i, n)| (VetoSource::Negative(i), n));
+        let texts = exclusion.texts.iter().zip(&exclusion.text_negatives).map(|(t, n)| (VetoSource::Text(t.clone()), n));
+        for (source, negative) in negatives.chain(texts) {
+            if negative.len() != input_vec.len() {
+                continue;
+            }
+            let similarity = metric.similarity(negative, input_vec, concept.covariance.as_ref());
+            if strongest.as_ref().is_none_or(|(_, s)| similarity > *s) {
+                strongest = Some((source, similarity));
+            }
+        }
+        for name in &exclusion.vetoes {
+            let Some(veto) = self.concept(name).or_else(|| self.veto_sources.iter().find(|c| c.name == *name)) else {
+                continue; // Only while a view's registry lacks it; the owning engine keeps vetoes resolvable
+            };
+            let similarity = self.score(veto, input_vec)?;
+            if strongest.as_ref().is_none_or(|(_, s)| similarity > *s) {
+                strongest = Some((VetoSource::Concept(name.clone()), similarity));
+            }
+        }
+        Ok(strongest
+            .filter(|&(_, similarity)| similarity > exclusion.margin)
+            .map(|(source, similarity)| Veto { source, similarity, margin: exclusion.margin, action: exclusion.action }))
+    }
+
+    // `similarity` after any veto against `concept`; `None` when the match is suppressed
+    pub(crate) fn screened(&self, concept: &ConceptVector, input_vec: &Array1<f32>, similarity: f32) -> Result<Option<f32>, SimilarityError> {
+        if concept.exclusion.is_none() {
+            return Ok(Some(similarity));
+        }
+        Ok(match self.veto(concept, input_vec)? {
+            Some(veto) => veto.apply(similarity),
+            None => Some(similarity),
+        })
+    }
+
+    // Highest screened candidate above its threshold (see `rank_score` for mixed metrics);
+    // ties go to the later concept. `thresholds` comes from `draw_thresholds`.
+    pub(crate) fn best_candidate(
+        &self,
+        input_vec: &Array1<f32>,
+        candidates: impl IntoIterator<Item = (usize, f32)>,
+        thresholds: &[f32],
+    ) -> Result<Option<(usize, f32)>, SimilarityError> {
+        let mixed = self.mixed_metrics();
+        let mut best: Option<(usize, f32, f32)> = None;
+        for (i, similarity) in candidates {
+            let concept = &self.concepts[i];
+            let Some(similarity) = self.screened(concept, input_vec, similarity)? else {
+                continue;
+            };
+            let threshold = thresholds[i];
+            let rank = Self::rank_score(similarity, threshold, mixed);
+            if similarity > threshold && best.is_none_or(|(_, _, r)| rank >= r) {
+                best = Some((i, similarity, rank));
+            }
+        }
+        Ok(best.map(|(i, similarity, _)| (i, similarity)))
+    }
+
+    // Names of concepts vetoing through `name`
+    pub fn vetoed_by(&self, name: &str) -> Vec<&str> {
+        self.concepts.iter()
+            .filter(|c| c.exclusion.as_ref().is_some_and(|e| e.vetoes.iter().any(|v| v == name)))
+            .map(|c| c.name.as_str())
+            .collect()
+    }
+
+    // Veto concepts named by these concepts but held elsewhere; views supply them from the registry
+    pub(crate) fn set_veto_sources(&mut self, sources: Vec<ConceptVector>) {
+        self.veto_sources = sources;
+    }
+
+    // Drops every reference to a deleted concept
+    pub(crate) fn drop_vetoes(&mut self, name: &str) {
+        let mut changed = Vec::new();
+        for concept in self.concepts.iter_mut().chain(self.retired.iter_mut()) {
+            let Some(exclusion) = concept.exclusion.as_mut() else {
+                continue;
+            };
+            if exclusion.vetoes.iter().any(|v| v == name) {
+                exclusion.vetoes.retain(|v| v != name);
+                if exclusion.is_empty() {
+                    concept.exclusion = None;
+                }
+                changed.push(concept.id);
+            }
+        }
+        for id in changed {
+            if let Some(concept) = self.concept_by_id(id).cloned() {
+                self.notify(ConceptEvent::Updated(concept));
+            }
+        }
+    }
+
+    // Sets or clears a concept's exclusion rule; veto concepts must exist and differ from it
+    pub fn set_exclusion(&mut self, name: &str, exclusion: Option<Exclusion>) -> Result<(), SimilarityError> {
+        let id = self.concept(name)
+            .ok_or_else(|| SimilarityError::UnknownConcept { name: name.to_string() })?
+            .id;
+        self.edit_concept(id, |concept| concept.exclusion = exclusion.filter(|e| !e.is_empty()))?;
+        Ok(())
+    }
+
+    // Embeds `text` with the attached backend and adds it to the concept's negatives
+    pub fn add_veto_text(&mut self, name: &str, text: &str) -> Result<(), SimilarityError> {
+        let embedder = self.embedder().ok_or(SimilarityError::NoEmbedder)?;
+        let negative = embedder.embed(text).map_err(|e| SimilarityError::embedding(embedder.model_id(), e))?;
+        let mut exclusion = self.concept(name)
+            .ok_or_else(|| SimilarityError::UnknownConcept { name: name.to_string() })?
+            .exclusion.clone()
+            .unwrap_or_default();
+        exclusion.texts.push(text.to_string());
+        exclusion.text_negatives.push(negative);
+        self.set_exclusion(name, Some(exclusion))
+    }
+
+    // Negatives must fit the concept and veto concepts must name other active concepts
+    pub(crate) fn check_exclusion(&self, concept: &ConceptVector) -> Result<(), SimilarityError> {
+        let Some(exclusion) = &concept.exclusion else {
+            return Ok(());
+        };
+        let invalid = |reason: String| SimilarityError::InvalidExclusion { concept: concept.name.clone(), reason };
+        if exclusion.texts.len() != exclusion.text_negatives.len() {
+            return Err(invalid(format!(
+                "{} negative texts but {} embeddings", exclusion.texts.len(), exclusion.text_negatives.len()
+            )));
+        }
+        for (i, negative) in exclusion.negatives.iter().chain(&exclusion.text_negatives).enumerate() {
+            if negative.len() != concept.vector.len() {
+                return Err(SimilarityError::mismatch(
+                    concept.vector.len(), negative.len(), format!("concept '{}' negative #{i}", concept.name)
+                ));
+            }
+        }
+        if !exclusion.margin.is_finite() {
+            return Err(invalid("veto margin must be a finite number".to_string()));
+        }
+        if let VetoAction::Penalize(penalty) = exclusion.action {
+            if !penalty.is_finite() || penalty < 0.0 {
+                return Err(invalid("veto penalty must be a non-negative number".to_string()));
+            }
+        }
+        for veto in &exclusion.vetoes {
+            match self.concept(veto) {
+                // The stored copy still carries the old name while a concept is being renamed
+                Some(other) if *veto == concept.name || (concept.id.is_assigned() && other.id == concept.id) => {
+                    return Err(invalid("a concept cannot veto itself".to_string()));
+                }
+                Some(_) => {}
+                None if *veto == concept.name => return Err(invalid("a concept cannot veto itself".to_string())),
+                None => return Err(invalid(format!("veto concept '{veto}' does not exist"))),
+            }
+        }
+        Ok(())
+    }
+
+    // Points veto references at a renamed or merged concept; a concept left naming
+    // itself drops that veto
+    pub(crate) fn rename_vetoes(&mut self, from: &str, to: &str) {
+        if from == to {
+            return;
+        }
+        let mut changed = Vec::new();
+        for concept in self.concepts.iter_mut().chain(self.retired.iter_mut()) {
+            let name = concept.name.clone();
+            let Some(exclusion) = concept.exclusion.as_mut() else {
+                continue;
+            };
+            if !exclusion.vetoes.iter().any(|v| v == from) {
+                continue;
+            }
+            for veto in exclusion.vetoes.iter_mut().filter(|v| *v == from) {
+                *veto = to.to_string();
+            }
+            exclusion.vetoes.retain(|v| *v != name);
+            let mut seen = HashSet::new();
+            exclusion.vetoes.retain(|v| seen.insert(v.clone()));
+            if exclusion.is_empty() {
+                concept.exclusion = None;
+            }
+            changed.push(concept.id);
+        }
+        for id in changed {
+            if let Some(concept) = self.concept_by_id(id).cloned() {
+                self.notify(ConceptEvent::Updated(concept));
+            }
+        }
+    }
+}
+
+impl fmt::Display for Veto {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.source {
+            VetoSource::Negative(i) => write!(f, "vetoed by negative #{i}")?,
+            VetoSource::Text(text) => write!(f, "vetoed by negative “{text}”")?,
+            VetoSource::Concept(name) => write!(f, "vetoed by '{name}'")?,
+        }
+        write!(f, " ({:.3} > {:.3}", self.similarity, self.margin)?;
+        match self.action {
+            VetoAction::Suppress => write!(f, ", suppressed)"),
+            VetoAction::Penalize(penalty) => write!(f, ", -{penalty:.3})"),
+        }
+    }
+}
@@ -0,0 +1,150 @@
+// #FF69B4 Ranked Matches & Explanations
+use super::{ConceptVector, HierarchicalMatch, SimilarityEngine, SimilarityError, SimilarityMetric, Veto};
+use ndarray::Array1;
+use std::fmt;
+
+// One ranked candidate for an input
+#[derive(Debug, Clone)]
+pub struct MatchResult {
+    pub concept: ConceptVector,
+    pub path: String,             // Taxonomy path, e.g. "Verification/Code review"
+    pub similarity: f32,
+    pub metric: SimilarityMetric, // Units of `similarity` and the threshold
+    pub prototype: Option<usize>, // Exemplar that fired, for multi-prototype concepts
+    pub threshold: f32,           // Threshold applied, including any jitter
+    pub passed_threshold: bool,
+    pub margin: f32, // similarity - threshold; negative when the concept did not fire
+    pub veto: Option<Veto>, // Exclusion that suppressed or penalized this concept
+}
+
+// Auditable record of why an input matched (or did not)
+#[derive(Debug, Clone)]
+pub struct MatchExplanation {
+    pub model_id: Option<String>,
+    pub candidates: Vec<MatchResult>, // Best first
+    pub winner: Option<MatchResult>, // What `find_best_match` would return, even if ranked below `k`
+    pub lead: Option<f32>, // Winner similarity minus the best other candidate (margins when metrics are mixed)
+    pub refined: Option<HierarchicalMatch>, // Deepest taxonomy match under the same thresholds, when there is a taxonomy
+}
+
+impl SimilarityEngine {
+    // The `k` most similar concepts, best first, whether or not they pass their threshold.
+    // Penalties are already taken off `similarity`; suppressed concepts keep theirs but fail.
+    // Concepts in different metrics are ordered by margin, since their scores don't compare.
+    pub fn top_k(&self, input_vec: &Array1<f32>, k: usize) -> Result<Vec<MatchResult>, SimilarityError> {
+        self.check_dimension(input_vec.len(), "query")?;
+        self.ranked(input_vec, k, &self.draw_thresholds())
+    }
+
+    fn ranked(&self, input_vec: &Array1<f32>, k: usize, thresholds: &[f32]) -> Result<Vec<MatchResult>, SimilarityError> {
+        let mut results = Vec::with_capacity(self.concepts.len());
+        for (concept, &threshold) in self.concepts.iter().zip(thresholds) {
+            let (raw, prototype) = self.score_with_prototype(concept, input_vec)?;
+            let veto = self.veto(concept, input_vec)?;
+            let screened = veto.as_ref().map_or(Some(raw), |v| v.apply(raw));
+            let similarity = screened.unwrap_or(raw);
+            results.push(MatchResult {
+                concept: concept.clone(),
+                path: String::new(),
+                similarity,
+                metric: self.metric_for(concept),
+                prototype: concept.is_multi_prototype().then_some(prototype),
+                threshold,
+                passed_threshold: screened.is_some_and(|s| s > threshold),
+                margin: similarity - threshold,
+                veto,
+            });
+        }
+        let mixed = self.mixed_metrics();
+        let rank = |r: &MatchResult| Self::rank_score(r.similarity, r.threshold, mixed);
+        results.sort_by(|a, b| rank(b).total_cmp(&rank(a)));
+        results.truncate(k);
+        for result in &mut results {
+            result.path = self.path_of(&result.concept.name).unwrap_or_else(|| result.concept.name.clone());
+        }
+        Ok(results)
+    }
+
+    // Ranks the top `k` candidates and names the winner `find_best_match` would pick.
+    // Jitter is drawn once, so the winner and `refined` agree with the listed thresholds.
+    pub fn explain(&self, input_vec: &Array1<f32>, k: usize) -> Result<MatchExplanation, SimilarityError> {
+        self.check_dimension(input_vec.len(), "query")?;
+        let thresholds = self.draw_thresholds();
+        let ranked = self.ranked(input_vec, self.concepts.len(), &thresholds)?;
+        let refined = if self.has_taxonomy() {
+            self.hierarchical_match(input_vec, &thresholds)?
+        } else {
+            None
+        };
+
+        let mixed = self.mixed_metrics();
+        let rank = |r: &MatchResult| Self::rank_score(r.similarity, r.threshold, mixed);
+        let winner_index = ranked.iter().position(|r| r.passed_threshold);
+        let lead = winner_index.map(|w| {
+            let runner_up = ranked.iter().enumerate()
+                .find(|(i, _)| *i != w)
+                .map(|(_, r)| rank(r));
+            runner_up.map_or(rank(&ranked[w]), |s| rank(&ranked[w]) - s)
+        });
+
+        Ok(MatchExplanation {
+            model_id: self.embedder().map(|e| e.model_id().to_string()),
+            winner: winner_index.map(|w| ranked[w].clone()),
+            lead,
+            refined,
+            candidates: ranked.into_iter().take(k).collect(),
+        })
+    }
+}
+
+impl MatchExplanation {
+    // Single-line form for action logs
+    pub fn summary(&self) -> String {
+        let scores: Vec<String> = self.candidates.iter()
+            .map(|c| match &c.veto {
+                Some(veto) => format!("{}={:.3} ({veto})", c.concept.name, c.similarity),
+                None => format!("{}={:.3}", c.concept.name, c.similarity),
+            })
+            .collect();
+        match (&self.winner, self.lead) {
+            (Some(winner), Some(lead)) => format!("winner {} (lead {lead:.3}); {}", winner.concept.name, scores.join(", ")),
+            _ => format!("no match; {}", scores.join(", ")),
+        }
+    }
+}
+
+impl fmt::Display for MatchResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {:<16} {} {:.3}  threshold {:.3}  margin {:+.3}",
+            if self.passed_threshold { "✔" } else { "✘" },
+            self.path, self.metric, self.similarity, self.threshold, self.margin
+        )?;
+        if let Some(prototype) = self.prototype {
+            write!(f, "  via prototype #{prototype} ({})", self.concept.aggregation)?;
+        }
+        if let Some(veto) = &self.veto {
+            write!(f, "  {veto}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for MatchExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.winner, self.lead) {
+            (Some(winner), Some(lead)) => {
+                writeln!(f, "Winner: {} (lead over runner-up {lead:.3})", winner.concept.name)?
+            }
+            _ => writeln!(f, "Winner: none (no concept passed its threshold)")?,
+        }
+        if let Some(model) = &self.model_id {
+            writeln!(f, "Model: {model}")?;
+        }
+        for (rank, candidate) in self.candidates.iter().enumerate() {
+            writeln!(f, "  {}. {candidate}", rank + 1)?;
+        }
+        Ok(())
+    }
+}
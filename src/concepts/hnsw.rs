@@ -0,0 +1,391 @@
+// #FF69B4 Approximate Nearest-Neighbour Index (HNSW)
+use super::{SimilarityEngine, SimilarityError};
+use ndarray::{Array1, Array2, ArrayView1};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+// Tuning knobs for the hierarchical navigable small-world graph
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HnswConfig {
+    pub m: usize,               // Links per node on upper layers (twice this on layer 0)
+    pub ef_construction: usize, // Candidate list size while inserting
+    pub ef_search: usize,       // Candidate list size while querying; higher is slower but more accurate
+    pub exact_below: usize,     // Engines with fewer concepts keep using the exact scan
+    pub seed: u64,              // Layer assignment is reproducible for a given seed
+}
+
+impl Default for HnswConfig {
+    fn default() -> Self {
+        HnswConfig {
+            m: 16,
+            ef_construction: 200,
+            ef_search: 64,
+            exact_below: 1000,
+            seed: 0,
+        }
+    }
+}
+
+// Similarity with a total order so it can live in a heap
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Scored(f32, usize);
+
+impl Eq for Scored {}
+
+impl PartialOrd for Scored {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Scored {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0).then(self.1.cmp(&other.1))
+    }
+}
+
+struct Node {
+    key: usize,
+    vector: Array1<f32>,    // Unit length, so similarity is a dot product
+    links: Vec<Vec<usize>>, // Neighbour slots per layer, layer 0 first
+    deleted: bool,
+}
+
+// Graph over unit vectors keyed by caller-chosen ids (concept positions in the engine).
+// Deletes are tombstones; the graph is compacted once they outnumber live nodes.
+pub struct HnswIndex {
+    config: HnswConfig,
+    nodes: Vec<Node>,
+    slots: HashMap<usize, usize>, // key -> node slot
+    entry: Option<usize>,
+    rng: StdRng,
+}
+
+impl HnswIndex {
+    pub fn new(config: HnswConfig) -> Self {
+        HnswIndex {
+            config,
+            nodes: Vec::new(),
+            slots: HashMap::new(),
+            entry: None,
+            rng: StdRng::seed_from_u64(config.seed),
+        }
+    }
+
+    // Builds an index over the rows of `vectors`, keyed by row number
+    pub fn from_rows(config: HnswConfig, vectors: &Array2<f32>) -> Self {
+        let mut index = Self::new(config);
+        for (key, row) in vectors.rows().into_iter().enumerate() {
+            index.insert(key, row);
+        }
+        index
+    }
+
+    pub fn config(&self) -> HnswConfig {
+        self.config
+    }
+
+    // Number of live (not deleted) entries
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    // Bytes held by node vectors and neighbour lists
+    pub fn memory_bytes(&self) -> usize {
+        self.nodes.iter()
+            .map(|n| n.vector.len() * size_of::<f32>() + n.links.iter().map(|l| l.len() * size_of::<usize>()).sum::<usize>())
+            .sum()
+    }
+
+    pub fn contains(&self, key: usize) -> bool {
+        self.slots.contains_key(&key)
+    }
+
+    // Adds or replaces the vector stored under `key`
+    pub fn insert(&mut self, key: usize, vector: ArrayView1<f32>) {
+        self.remove(key);
+
+        let mut vector = vector.to_owned();
+        let norm = vector.dot(&vector).sqrt();
+        if norm > 0.0 {
+            vector.mapv_inplace(|x| x / norm);
+        }
+
+        let level = self.random_level();
+        let slot = self.nodes.len();
+        self.nodes.push(Node { key, vector, links: vec![Vec::new(); level + 1], deleted: false });
+        self.slots.insert(key, slot);
+
+        let Some(entry) = self.entry else {
+            self.entry = Some(slot);
+            return;
+        };
+
+        let query = self.nodes[slot].vector.clone();
+        let top = self.top_level();
+        let mut nearest = vec![entry];
+        for layer in (level + 1..=top).rev() {
+            nearest = vec![self.search_layer(&query, &nearest, 1, layer)[0].1];
+        }
+
+        for layer in (0..=level.min(top)).rev() {
+            let candidates = self.search_layer(&query, &nearest, self.config.ef_construction, layer);
+            let neighbours = self.select_neighbours(&candidates, self.config.m);
+            for &neighbour in &neighbours {
+                self.connect(neighbour, slot, layer);
+            }
+            self.nodes[slot].links[layer] = neighbours;
+            nearest = candidates.iter().map(|s| s.1).collect();
+        }
+
+        if level > top {
+            self.entry = Some(slot);
+        }
+    }
+
+    // Tombstones `key`; returns whether it was present
+    pub fn remove(&mut self, key: usize) -> bool {
+        let Some(slot) = self.slots.remove(&key) else {
+            return false;
+        };
+        self.nodes[slot].deleted = true;
+        if self.nodes.len() > 2 * self.slots.len() + self.config.m {
+            self.compact();
+        }
+        true
+    }
+
+    // Shifts keys down after the caller removed position `removed` from its own list
+    pub fn relabel_after_removal(&mut self, removed: usize) {
+        for node in self.nodes.iter_mut().filter(|n| !n.deleted && n.key > removed) {
+            node.key -= 1;
+        }
+        self.slots = self.nodes.iter().enumerate()
+            .filter(|(_, n)| !n.deleted)
+            .map(|(slot, n)| (n.key, slot))
+            .collect();
+    }
+
+    // Up to `k` (key, similarity) pairs, most similar first
+    pub fn search(&self, query: &Array1<f32>, k: usize) -> Vec<(usize, f32)> {
+        let Some(entry) = self.entry else {
+            return Vec::new();
+        };
+        let mut query = query.clone();
+        let norm = query.dot(&query).sqrt();
+        if norm > 0.0 {
+            query.mapv_inplace(|x| x / norm);
+        }
+
+        let mut nearest = vec![entry];
+        for layer in (1..=self.top_level()).rev() {
+            nearest = vec![self.search_layer(&query, &nearest, 1, layer)[0].1];
+        }
+        // Tombstones still route the search but never appear in results
+        let ef = self.config.ef_search.max(k) + (self.nodes.len() - self.slots.len()).min(k);
+        self.search_layer(&query, &nearest, ef, 0)
+            .into_iter()
+            .filter(|s| !self.nodes[s.1].deleted)
+            .take(k)
+            .map(|s| (self.nodes[s.1].key, s.0))
+            .collect()
+    }
+
+    fn top_level(&self) -> usize {
+        self.entry.map_or(0, |e| self.nodes[e].links.len() - 1)
+    }
+
+    fn random_level(&mut self) -> usize {
+        let scale = 1.0 / (self.config.m.max(2) as f64).ln();
+        let uniform: f64 = self.rng.gen_range(f64::EPSILON..1.0);
+        ((-uniform.ln() * scale) as usize).min(16)
+    }
+
+    fn max_links(&self, layer: usize) -> usize {
+        if layer == 0 { 2 * self.config.m } else { self.config.m }
+    }
+
+    fn similarity(&self, query: &Array1<f32>, slot: usize) -> f32 {
+        query.dot(&self.nodes[slot].vector)
+    }
+
+    // Best-first search of one layer; returns up to `ef` slots, most similar first
+    fn search_layer(&self, query: &Array1<f32>, entries: &[usize], ef: usize, layer: usize) -> Vec<Scored> {
+        let mut visited: HashSet<usize> = entries.iter().copied().collect();
+        let mut candidates: BinaryHeap<Scored> = BinaryHeap::new();
+        let mut found: BinaryHeap<Reverse<Scored>> = BinaryHeap::new();
+        for &slot in entries {
+            let scored = Scored(self.similarity(query, slot), slot);
+            candidates.push(scored);
+            found.push(Reverse(scored));
+        }
+
+        while let Some(current) = candidates.pop() {
+            let worst = found.peek().map_or(f32::NEG_INFINITY, |r| r.0 .0);
+            if current.0 < worst && found.len() >= ef {
+                break;
+            }
+            let Some(links) = self.nodes[current.1].links.get(layer) else {
+                continue;
+            };
+            for &neighbour in links {
+                if !visited.insert(neighbour) {
+                    continue;
+                }
+                let scored = Scored(self.similarity(query, neighbour), neighbour);
+                let worst = found.peek().map_or(f32::NEG_INFINITY, |r| r.0 .0);
+                if found.len() < ef || scored.0 > worst {
+                    candidates.push(scored);
+                    found.push(Reverse(scored));
+                    if found.len() > ef {
+                        found.pop();
+                    }
+                }
+            }
+        }
+
+        let mut results: Vec<Scored> = found.into_iter().map(|r| r.0).collect();
+        results.sort_by(|a, b| b.cmp(a));
+        results
+    }
+
+    // Neighbour heuristic from the HNSW paper: skip candidates already closer to a chosen
+    // neighbour than to the query, then top up with the skipped ones
+    fn select_neighbours(&self, candidates: &[Scored], m: usize) -> Vec<usize> {
+        let mut chosen: Vec<usize> = Vec::with_capacity(m);
+        let mut skipped = Vec::new();
+        for candidate in candidates {
+            if chosen.len() >= m {
+                break;
+            }
+            let vector = &self.nodes[candidate.1].vector;
+            if chosen.iter().all(|&c| vector.dot(&self.nodes[c].vector) < candidate.0) {
+                chosen.push(candidate.1);
+            } else {
+                skipped.push(candidate.1);
+            }
+        }
+        for slot in skipped {
+            if chosen.len() >= m {
+                break;
+            }
+            chosen.push(slot);
+        }
+        chosen
+    }
+
+    // Adds a back-link, pruning the neighbour's list when it grows past its limit
+    fn connect(&mut self, from: usize, to: usize, layer: usize) {
+        self.nodes[from].links[layer].push(to);
+        let limit = self.max_links(layer);
+        if self.nodes[from].links[layer].len() <= limit {
+            return;
+        }
+        let origin = self.nodes[from].vector.clone();
+        let mut scored: Vec<Scored> = self.nodes[from].links[layer].iter()
+            .map(|&slot| Scored(self.similarity(&origin, slot), slot))
+            .collect();
+        scored.sort_by(|a, b| b.cmp(a));
+        self.nodes[from].links[layer] = self.select_neighbours(&scored, limit);
+    }
+
+    // Rebuilds the graph from the live nodes only
+    fn compact(&mut self) {
+        let live: Vec<(usize, Array1<f32>)> = std::mem::take(&mut self.nodes).into_iter()
+            .filter(|n| !n.deleted)
+            .map(|n| (n.key, n.vector))
+            .collect();
+        self.slots.clear();
+        self.entry = None;
+        for (key, vector) in live {
+            self.insert(key, vector.view());
+        }
+    }
+}
+
+impl SimilarityEngine {
+    // Enables approximate search once the engine holds at least `config.exact_below` concepts
+    pub fn with_index(mut self, config: HnswConfig) -> Self {
+        self.set_index(Some(config));
+        self
+    }
+
+    // Turns the index on or off; it is (re)built lazily on the next query
+    pub fn set_index(&mut self, config: Option<HnswConfig>) {
+        self.index_config = config;
+        *self.index.get_mut().unwrap_or_else(|p| p.into_inner()) = None;
+    }
+
+    pub fn index_config(&self) -> Option<HnswConfig> {
+        self.index_config
+    }
+
+    // Whether queries currently go through the index rather than the exact scan.
+    // The graph holds one cosine vector per concept, so other metrics and
+    // multi-prototype concepts force the exact scan.
+    pub fn uses_index(&self) -> bool {
+        self.index_config.is_some_and(|c| self.concepts.len() >= c.exact_below.max(1)) && self.matrix_compatible()
+    }
+
+    // Fraction of the exact top-`k` concepts that the index also returns, averaged over
+    // the query rows. Used to tune ef/M against a brute-force baseline.
+    pub fn index_recall(&self, queries: &Array2<f32>, k: usize) -> Result<f32, SimilarityError> {
+        if queries.nrows() == 0 || k == 0 {
+            return Ok(1.0);
+        }
+        let exact = self.score_batch(queries)?;
+        let mut hits = 0usize;
+        let mut expected = 0usize;
+        for (query, scores) in queries.rows().into_iter().zip(exact.rows()) {
+            let mut ranked: Vec<usize> = (0..scores.len()).collect();
+            ranked.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]));
+            ranked.truncate(k);
+
+            let approximate: HashSet<usize> = self.index_search(&query.to_owned(), k)?
+                .into_iter()
+                .map(|(key, _)| key)
+                .collect();
+            hits += ranked.iter().filter(|key| approximate.contains(key)).count();
+            expected += ranked.len();
+        }
+        Ok(if expected == 0 { 1.0 } else { hits as f32 / expected as f32 })
+    }
+
+    // Candidate concepts from the index, building it first if needed
+    pub(crate) fn index_search(&self, query: &Array1<f32>, k: usize) -> Result<Vec<(usize, f32)>, SimilarityError> {
+        let config = self.index_config.unwrap_or_default();
+        {
+            let index = self.index.read().unwrap_or_else(|p| p.into_inner());
+            if let Some(index) = index.as_ref().filter(|i| i.len() == self.concepts.len()) {
+                return Ok(index.search(query, k));
+            }
+        }
+
+        let matrix = self.concept_matrix()?;
+        let index = HnswIndex::from_rows(config, &matrix);
+        let results = index.search(query, k);
+        *self.index.write().unwrap_or_else(|p| p.into_inner()) = Some(index);
+        Ok(results)
+    }
+
+    // Keeps a built index in step with `add_concept` / `remove_concept`
+    pub(crate) fn index_inserted(&mut self, position: usize) {
+        let vector = self.concepts[position].vector.view();
+        if let Some(index) = self.index.get_mut().unwrap_or_else(|p| p.into_inner()).as_mut() {
+            index.insert(position, vector);
+        }
+    }
+
+    pub(crate) fn index_removed(&mut self, position: usize) {
+        if let Some(index) = self.index.get_mut().unwrap_or_else(|p| p.into_inner()).as_mut() {
+            index.remove(position);
+            index.relabel_after_removal(position);
+        }
+    }
+}
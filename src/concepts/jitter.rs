@@ -0,0 +1,55 @@
+// #FF69B4 Stochastic Threshold Jitter
+use super::{ConceptVector, SimilarityEngine};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+// "0.65 ± random_variation": a concept's threshold wobbles by up to `amplitude`.
+// Half of the shift is a lean set by the stochastic state (an exploratory state,
+// `[1, 0]`, lowers the bar; a cautious one, `[0, 1]`, raises it) and half is noise.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ThresholdJitter {
+    pub amplitude: f32,
+}
+
+impl ThresholdJitter {
+    // `noise` is uniform in [-1, 1]
+    pub fn offset(&self, stochastic_state: [f32; 2], noise: f32) -> f32 {
+        let lean = (stochastic_state[1] - stochastic_state[0]).clamp(-1.0, 1.0);
+        self.amplitude * 0.5 * (lean + noise.clamp(-1.0, 1.0))
+    }
+}
+
+impl SimilarityEngine {
+    // Reseeds the jitter RNG so a run can be replayed
+    pub fn with_jitter_seed(self, seed: u64) -> Self {
+        *self.jitter_rng.lock().unwrap_or_else(|p| p.into_inner()) = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    // With jitter disabled every concept uses its fixed threshold
+    pub fn set_jitter_enabled(&mut self, enabled: bool) {
+        self.jitter_enabled = enabled;
+    }
+
+    pub fn jitter_enabled(&self) -> bool {
+        self.jitter_enabled
+    }
+
+    // One threshold per active concept, drawn once per query so ranking, explanation
+    // and the hierarchy walk all hold a concept to the same bar
+    pub(crate) fn draw_thresholds(&self) -> Vec<f32> {
+        self.concepts.iter().map(|c| self.effective_threshold(c)).collect()
+    }
+
+    // The threshold a concept is held to for this comparison
+    fn effective_threshold(&self, concept: &ConceptVector) -> f32 {
+        match concept.jitter {
+            Some(jitter) if self.jitter_enabled && jitter.amplitude != 0.0 => {
+                let noise = self.jitter_rng.lock().unwrap_or_else(|p| p.into_inner()).gen_range(-1.0..=1.0);
+                concept.threshold + jitter.offset(concept.stochastic_state, noise)
+            }
+            _ => concept.threshold,
+        }
+    }
+}
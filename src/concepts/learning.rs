@@ -0,0 +1,175 @@
+// #FF69B4 Online Prototype Learning
+use super::{cosine_similarity, ConceptVector, SimilarityEngine, SimilarityError};
+use ndarray::Array1;
+use serde::{Deserialize, Serialize};
+
+// Steps of the drift clamp's bisection; plenty for f32 precision
+const CLAMP_STEPS: usize = 24;
+
+// How confirmed matches move a concept towards its inputs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LearningConfig {
+    pub learning_rate: f32, // Fraction of the gap to the input closed per confirmation
+    pub momentum: f32,      // Share of the previous step carried into the next
+    pub max_drift: f32,     // Largest cosine distance allowed from the original vector
+}
+
+impl Default for LearningConfig {
+    fn default() -> Self {
+        LearningConfig {
+            learning_rate: 0.05,
+            momentum: 0.5,
+            max_drift: 0.25,
+        }
+    }
+}
+
+impl LearningConfig {
+    // A rate in (0, 1], momentum in [0, 1) and a finite, non-negative drift limit
+    pub fn validate(&self) -> Result<(), SimilarityError> {
+        let invalid = |setting: &str, reason: &str| Err(SimilarityError::InvalidConfig {
+            setting: setting.to_string(),
+            reason: reason.to_string(),
+        });
+        if !(self.learning_rate > 0.0 && self.learning_rate <= 1.0) {
+            return invalid("learning rate", &format!("{} is not in (0, 1]", self.learning_rate));
+        }
+        if !(0.0..1.0).contains(&self.momentum) {
+            return invalid("momentum", &format!("{} is not in [0, 1)", self.momentum));
+        }
+        if !self.max_drift.is_finite() || self.max_drift < 0.0 {
+            return invalid("max drift", &format!("{} is not a finite, non-negative distance", self.max_drift));
+        }
+        Ok(())
+    }
+}
+
+// What a concept looked like before learning, plus the running update
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LearnedState {
+    pub original: Array1<f32>,
+    pub velocity: Array1<f32>,
+    pub updates: u64,
+}
+
+// Per-concept learning summary
+#[derive(Debug, Clone, PartialEq)]
+pub struct DriftStats {
+    pub concept: String,
+    pub drift: f32, // Cosine distance from the original vector
+    pub updates: u64,
+}
+
+impl ConceptVector {
+    // Cosine distance between the current and original vector; zero if never trained
+    pub fn drift(&self) -> f32 {
+        self.learned.as_ref().map_or(0.0, |state| 1.0 - cosine_similarity(&state.original, &self.vector))
+    }
+}
+
+impl SimilarityEngine {
+    // Turns on learning from `confirm_match`; off by default
+    pub fn with_learning(mut self, config: LearningConfig) -> Result<Self, SimilarityError> {
+        self.set_learning(Some(config))?;
+        Ok(self)
+    }
+
+    // Refuses configs `validate` rejects, keeping the previous one
+    pub fn set_learning(&mut self, config: Option<LearningConfig>) -> Result<(), SimilarityError> {
+        if let Some(config) = &config {
+            config.validate()?;
+        }
+        self.learning = config;
+        Ok(())
+    }
+
+    pub fn learning(&self) -> Option<LearningConfig> {
+        self.learning
+    }
+
+    // Nudges a confirmed concept towards the input it matched and returns its new drift,
+    // or `None` when learning is off. Multi-prototype concepts can't learn and are refused.
+    pub fn confirm_match(&mut self, name: &str, input_vec: &Array1<f32>) -> Result<Option<f32>, SimilarityError> {
+        let position = self.position_of(name)?;
+        self.check_dimension(input_vec.len(), "confirmed input")?;
+        let Some(config) = self.learning else {
+            return Ok(None);
+        };
+        if self.concepts[position].is_multi_prototype() {
+            return Err(SimilarityError::InvalidOperation {
+                concept: name.to_string(),
+                reason: "multi-prototype concepts don't learn from confirmations".to_string(),
+            });
+        }
+
+        let concept = &mut self.concepts[position];
+        let state = concept.learned.get_or_insert_with(|| LearnedState {
+            original: concept.vector.clone(),
+            velocity: Array1::zeros(concept.vector.len()),
+            updates: 0,
+        });
+
+        // Pull towards the input's direction at the concept's own magnitude
+        let scale = concept.vector.dot(&concept.vector).sqrt();
+        let input_norm = input_vec.dot(input_vec).sqrt();
+        if input_norm > 0.0 {
+            let target = input_vec * (scale.max(f32::EPSILON) / input_norm);
+            let step = (&target - &concept.vector) * config.learning_rate;
+            state.velocity = &state.velocity * config.momentum + step;
+            let mut updated = &concept.vector + &state.velocity;
+
+            if 1.0 - cosine_similarity(&state.original, &updated) > config.max_drift {
+                updated = clamp_drift(&state.original, &updated, config.max_drift);
+                state.velocity.fill(0.0);
+            }
+            concept.vector = updated;
+        }
+        state.updates += 1;
+
+        let drift = concept.drift();
+        self.concept_changed(position);
+        Ok(Some(drift))
+    }
+
+    // Restores a concept's original vector and forgets its learning history
+    pub fn reset_concept(&mut self, name: &str) -> Result<(), SimilarityError> {
+        let position = self.position_of(name)?;
+        if let Some(state) = self.concepts[position].learned.take() {
+            self.concepts[position].vector = state.original;
+            self.concept_changed(position);
+        }
+        Ok(())
+    }
+
+    // Drift of every concept that has learned at least once
+    pub fn learning_stats(&self) -> Vec<DriftStats> {
+        self.concepts.iter()
+            .filter_map(|concept| concept.learned.as_ref().map(|state| DriftStats {
+                concept: concept.name.clone(),
+                drift: concept.drift(),
+                updates: state.updates,
+            }))
+            .collect()
+    }
+
+    fn position_of(&self, name: &str) -> Result<usize, SimilarityError> {
+        self.concepts.iter()
+            .position(|c| c.name == name)
+            .ok_or_else(|| SimilarityError::UnknownConcept { name: name.to_string() })
+    }
+}
+
+// Furthest point on the segment original → updated that stays within `max_drift`
+fn clamp_drift(original: &Array1<f32>, updated: &Array1<f32>, max_drift: f32) -> Array1<f32> {
+    let (mut low, mut high) = (0.0f32, 1.0f32);
+    for _ in 0..CLAMP_STEPS {
+        let mid = (low + high) / 2.0;
+        let candidate = original + &((updated - original) * mid);
+        if 1.0 - cosine_similarity(original, &candidate) > max_drift {
+            high = mid;
+        } else {
+            low = mid;
+        }
+    }
+    original + &((updated - original) * low)
+}
@@ -0,0 +1,425 @@
+// #FF69B4 Concept Lifecycle (managed edits, merge, split, retire)
+use super::discovery::kmeans;
+use super::prototypes::centroid;
+use super::{ConceptVector, SimilarityEngine, SimilarityError};
+use ndarray::{Array1, Array2, Axis};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+use std::sync::mpsc::{channel, Receiver};
+
+// Matched inputs remembered per concept for `split_concept`
+const HISTORY_CAPACITY: usize = 256;
+const SPLIT_ITERATIONS: usize = 25;
+
+// Stable identity of a concept within one engine; survives renames and edits.
+// Zero means "not assigned yet" and is replaced when the concept is added.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ConceptId(pub u64);
+
+impl ConceptId {
+    pub fn is_assigned(&self) -> bool {
+        self.0 != 0
+    }
+
+    pub(crate) fn is_unassigned(&self) -> bool {
+        !self.is_assigned()
+    }
+}
+
+impl fmt::Display for ConceptId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+// Sent to every subscriber after the engine's concept set changes
+#[derive(Debug, Clone)]
+pub enum ConceptEvent {
+    Added(ConceptVector),
+    Updated(ConceptVector), // Edited in place, including renames and learning steps
+    Removed(ConceptId),
+    Retired(ConceptId),
+    Restored(ConceptVector),
+    Merged { into: ConceptVector, absorbed: ConceptId },
+    Split { from: ConceptId, into: Vec<ConceptVector> },
+}
+
+impl SimilarityEngine {
+    pub fn concept_by_id(&self, id: ConceptId) -> Option<&ConceptVector> {
+        self.concepts.iter().find(|c| c.id == id)
+    }
+
+    pub fn id_of(&self, name: &str) -> Option<ConceptId> {
+        self.concept(name).map(|c| c.id)
+    }
+
+    // Soft-retired concepts, newest last; they are never matched
+    pub fn retired_concepts(&self) -> &[ConceptVector] {
+        &self.retired
+    }
+
+    // Receives every later change to the concept set
+    pub fn subscribe(&mut self) -> Receiver<ConceptEvent> {
+        let (sender, receiver) = channel();
+        self.subscribers.push(sender);
+        receiver
+    }
+
+    // Applies `edit` to a concept and re-checks it; a rejected edit leaves the concept untouched.
+    // The id cannot be changed; children follow a rename.
+    pub fn edit_concept(&mut self, id: ConceptId, edit: impl FnOnce(&mut ConceptVector)) -> Result<&ConceptVector, SimilarityError> {
+        let position = self.position_of_id(id)?;
+        let mut edited = self.concepts[position].clone();
+        edit(&mut edited);
+        edited.id = id;
+
+        let old_name = self.concepts[position].name.clone();
+        if edited.name != old_name {
+            self.check_unique_name(&edited.name)?;
+        }
+        self.check_concept(&edited)?;
+        self.check_exclusion(&edited)?;
+        if let Some(parent) = edited.parent.as_deref() {
+            if parent == edited.name || self.concept(parent).is_none_or(|p| p.id == id) {
+                return Err(SimilarityError::InvalidTaxonomy {
+                    concept: edited.name.clone(),
+                    reason: format!("parent '{parent}' does not exist"),
+                });
+            }
+        }
+
+        let new_name = edited.name.clone();
+        let previous = std::mem::replace(&mut self.concepts[position], edited);
+        self.reparent(&old_name, &new_name);
+        if let Err(e) = self.validate_taxonomy() {
+            // Only the new parent link can have introduced a cycle; undo the whole edit
+            self.reparent(&new_name, &old_name);
+            self.concepts[position] = previous;
+            return Err(e);
+        }
+        self.concept_changed(position);
+        self.rename_vetoes(&old_name, &new_name);
+        Ok(&self.concepts[position])
+    }
+
+    pub fn rename_concept(&mut self, id: ConceptId, name: &str) -> Result<(), SimilarityError> {
+        self.edit_concept(id, |concept| concept.name = name.to_string()).map(|_| ())
+    }
+
+    // Remembers an input that matched the concept; the history feeds `split_concept`
+    pub fn record_match(&mut self, id: ConceptId, input_vec: &Array1<f32>) -> Result<(), SimilarityError> {
+        self.position_of_id(id)?;
+        self.check_dimension(input_vec.len(), "matched input")?;
+        let norm = input_vec.dot(input_vec).sqrt();
+        if norm == 0.0 {
+            return Ok(());
+        }
+        let history = self.history.entry(id).or_default();
+        history.push_back(input_vec / norm);
+        while history.len() > HISTORY_CAPACITY {
+            history.pop_front();
+        }
+        Ok(())
+    }
+
+    pub fn match_history(&self, id: ConceptId) -> usize {
+        self.history.get(&id).map_or(0, VecDeque::len)
+    }
+
+    // Folds `absorbed` into `into`: both keep matching through the union of their
+    // prototypes, the more lenient threshold wins and stats are combined.
+    // `absorbed`'s children move to `into`.
+    pub fn merge_concepts(&mut self, into: ConceptId, absorbed: ConceptId) -> Result<&ConceptVector, SimilarityError> {
+        let keep = self.position_of_id(into)?;
+        let gone = self.position_of_id(absorbed)?;
+        if keep == gone {
+            return Err(self.invalid(into, "cannot merge a concept with itself"));
+        }
+        let (a, b) = (&self.concepts[keep], &self.concepts[gone]);
+        if self.metric_for(a) != self.metric_for(b) {
+            return Err(self.invalid(into, format!(
+                "uses {} similarity but '{}' uses {}", self.metric_for(a), b.name, self.metric_for(b)
+            )));
+        }
+
+        let mut merged = a.clone();
+        let prototypes: Vec<Array1<f32>> = a.prototype_vectors().into_iter()
+            .chain(b.prototype_vectors())
+            .cloned()
+            .collect();
+        merged = merged.with_prototypes(prototypes, a.aggregation);
+        merged.threshold = a.threshold.min(b.threshold);
+        merged.auto_threshold = a.auto_threshold && b.auto_threshold;
+        merged.curiosity_score = a.curiosity_score.max(b.curiosity_score);
+        merged.stochastic_state = [
+            (a.stochastic_state[0] + b.stochastic_state[0]) / 2.0,
+            (a.stochastic_state[1] + b.stochastic_state[1]) / 2.0,
+        ];
+        merged.last_interaction_time = a.last_interaction_time.max(b.last_interaction_time);
+        merged.covariance = match (&a.covariance, &b.covariance) {
+            (Some(x), Some(y)) => Some((x + y) / 2.0),
+            _ => None,
+        };
+        merged.learned = None; // The original vector no longer describes the merged concept
+        for text in &b.seed_texts {
+            if !merged.seed_texts.contains(text) {
+                merged.seed_texts.push(text.clone());
+            }
+        }
+        for text in &b.negative_texts {
+            if !merged.negative_texts.contains(text) {
+                merged.negative_texts.push(text.clone());
+            }
+        }
+        for (key, value) in &b.metadata {
+            merged.metadata.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+        if merged.parent.as_deref() == Some(b.name.as_str()) {
+            merged.parent = b.parent.clone();
+        } else if merged.parent.as_deref().is_some_and(|p| self.is_ancestor(&b.name, p)) {
+            // `absorbed`'s children would move under their own descendant
+            return Err(self.invalid(into, format!("descends from '{}'; merging would form a cycle", b.name)));
+        }
+        if merged.exclusion.is_none() {
+            merged.exclusion = b.exclusion.clone();
+        }
+        if let Some(exclusion) = merged.exclusion.as_mut() {
+            exclusion.vetoes.retain(|v| *v != b.name && *v != merged.name); // Would veto itself
+        }
+        merged.exclusion = merged.exclusion.take().filter(|e| !e.is_empty());
+
+        let absorbed_name = b.name.clone();
+        let merged_name = merged.name.clone();
+        self.concepts[keep] = merged;
+        self.reparent(&absorbed_name, &merged_name);
+        let absorbed_history = self.history.remove(&absorbed).unwrap_or_default();
+        let history = self.history.entry(into).or_default();
+        history.extend(absorbed_history);
+        while history.len() > HISTORY_CAPACITY {
+            history.pop_front();
+        }
+
+        self.take_concept(gone);
+        self.rename_vetoes(&absorbed_name, &merged_name);
+        let position = self.position_of_id(into)?;
+        self.invalidate_caches();
+        self.notify(ConceptEvent::Merged { into: self.concepts[position].clone(), absorbed });
+        Ok(&self.concepts[position])
+    }
+
+    // Replaces a concept with one concept per name, each centred on a k-means cluster of
+    // the inputs it matched. Thresholds and stats are copied; the original is removed.
+    pub fn split_concept(&mut self, id: ConceptId, names: &[&str]) -> Result<Vec<ConceptId>, SimilarityError> {
+        let position = self.position_of_id(id)?;
+        let original = &self.concepts[position];
+        if names.len() < 2 {
+            return Err(self.invalid(id, "a split needs at least two new names"));
+        }
+        if self.concepts.iter().any(|c| c.parent.as_deref() == Some(original.name.as_str())) {
+            return Err(self.invalid(id, "has child concepts; move them before splitting"));
+        }
+        // A part that keeps the name keeps the vetoes pointing at it
+        if !names.contains(&original.name.as_str()) {
+            let vetoing = self.vetoed_by(&original.name);
+            if !vetoing.is_empty() {
+                let reason = format!("vetoes matches of {}; drop those rules first", vetoing.join(", "));
+                return Err(self.invalid(id, &reason));
+            }
+        }
+        let mut unique = HashSet::new();
+        for name in names {
+            if !unique.insert(*name) {
+                return Err(SimilarityError::DuplicateConcept { name: name.to_string() });
+            }
+            if *name != original.name {
+                self.check_unique_name(name)?;
+            }
+        }
+
+        let history = self.history.get(&id).map(|h| h.iter().collect::<Vec<_>>()).unwrap_or_default();
+        if history.len() < names.len() {
+            return Err(self.invalid(id, format!(
+                "only {} matched inputs recorded, too few for {} parts", history.len(), names.len()
+            )));
+        }
+        let mut rows = Array2::zeros((history.len(), history[0].len()));
+        for (mut row, input) in rows.axis_iter_mut(Axis(0)).zip(&history) {
+            row.assign(input);
+        }
+        let clusters = kmeans(&rows, names.len(), SPLIT_ITERATIONS, id.0);
+        if clusters.len() < names.len() {
+            return Err(self.invalid(id, format!(
+                "matched inputs only form {} distinct groups", clusters.len()
+            )));
+        }
+
+        let mut parts = Vec::with_capacity(names.len());
+        for (name, members) in names.iter().zip(&clusters) {
+            let inputs: Vec<Array1<f32>> = members.iter().map(|&i| rows.row(i).to_owned()).collect();
+            let mut part = original.clone();
+            part.id = ConceptId::default();
+            part.name = name.to_string();
+            part.vector = centroid(&inputs);
+            part.prototypes.clear();
+            part.covariance = None;
+            part.learned = None;
+            part.seed_texts.clear(); // They described the whole concept
+            part.auto_threshold = false;
+            parts.push((part, inputs));
+        }
+
+        self.take_concept(position);
+        self.history.remove(&id);
+        let mut ids = Vec::with_capacity(parts.len());
+        let mut added = Vec::with_capacity(parts.len());
+        for (mut part, inputs) in parts {
+            part.id = self.fresh_id();
+            ids.push(part.id);
+            self.history.insert(part.id, inputs.into());
+            added.push(part.clone());
+            self.concepts.push(part);
+        }
+        self.invalidate_caches();
+        self.notify(ConceptEvent::Split { from: id, into: added });
+        Ok(ids)
+    }
+
+    // Takes a concept out of matching without losing it; `restore_concept` brings it back
+    pub fn retire_concept(&mut self, id: ConceptId) -> Result<(), SimilarityError> {
+        let position = self.position_of_id(id)?;
+        let name = &self.concepts[position].name;
+        if self.concepts.iter().any(|c| c.parent.as_deref() == Some(name.as_str())) {
+            return Err(self.invalid(id, "has active child concepts; retire them first"));
+        }
+        let vetoing = self.vetoed_by(name);
+        if !vetoing.is_empty() {
+            let reason = format!("vetoes matches of {}; drop those rules first", vetoing.join(", "));
+            return Err(self.invalid(id, &reason));
+        }
+        let concept = self.take_concept(position);
+        self.retired.push(concept);
+        self.invalidate_caches();
+        self.notify(ConceptEvent::Retired(id));
+        Ok(())
+    }
+
+    pub fn restore_concept(&mut self, id: ConceptId) -> Result<(), SimilarityError> {
+        let position = self.retired.iter().position(|c| c.id == id)
+            .ok_or_else(|| SimilarityError::UnknownConcept { name: id.to_string() })?;
+        if let Some(parent) = self.retired[position].parent.as_deref().filter(|p| self.concept(p).is_none()) {
+            return Err(SimilarityError::InvalidTaxonomy {
+                concept: self.retired[position].name.clone(),
+                reason: format!("parent '{parent}' is not active; restore it first"),
+            });
+        }
+        // Validate before taking it out, so a refused restore leaves it retired
+        self.check_concept(&self.retired[position])?;
+        self.check_exclusion(&self.retired[position])?;
+        let concept = self.retired.remove(position);
+        self.concepts.push(concept.clone());
+        self.invalidate_caches();
+        self.notify(ConceptEvent::Restored(concept));
+        Ok(())
+    }
+
+    // Gives unassigned or clashing concepts fresh ids; assigned ones are kept
+    pub(crate) fn assign_ids(&mut self) {
+        self.next_id = self.concepts.iter().chain(&self.retired).map(|c| c.id.0).max().unwrap_or(0);
+        let mut seen = HashSet::new();
+        for index in 0..self.concepts.len() {
+            let id = self.concepts[index].id;
+            if !id.is_assigned() || !seen.insert(id) {
+                let fresh = self.fresh_id();
+                self.concepts[index].id = fresh;
+                seen.insert(fresh);
+            }
+        }
+    }
+
+    pub(crate) fn fresh_id(&mut self) -> ConceptId {
+        self.next_id += 1;
+        ConceptId(self.next_id)
+    }
+
+    pub(crate) fn id_taken(&self, id: ConceptId) -> bool {
+        self.concepts.iter().chain(&self.retired).any(|c| c.id == id)
+    }
+
+    // Names stay unique across active and retired concepts so a restore never clashes
+    pub(crate) fn check_unique_name(&self, name: &str) -> Result<(), SimilarityError> {
+        if self.concepts.iter().chain(&self.retired).any(|c| c.name == name) {
+            return Err(SimilarityError::DuplicateConcept { name: name.to_string() });
+        }
+        Ok(())
+    }
+
+    // Points the children of `from` at `to`, retired ones included
+    fn reparent(&mut self, from: &str, to: &str) {
+        if from == to {
+            return;
+        }
+        for child in self.concepts.iter_mut().chain(self.retired.iter_mut()) {
+            if child.parent.as_deref() == Some(from) {
+                child.parent = Some(to.to_string());
+            }
+        }
+    }
+
+    // Whether `ancestor` is reached by following parents up from `name`
+    fn is_ancestor(&self, ancestor: &str, name: &str) -> bool {
+        let mut current = self.concept(name);
+        let mut steps = 0;
+        while let Some(concept) = current {
+            if concept.name == ancestor {
+                return true;
+            }
+            steps += 1;
+            if steps > self.concepts.len() {
+                return false; // Cycle; `validate_taxonomy` reports it
+            }
+            current = self.parent_of(&concept.name);
+        }
+        false
+    }
+
+    // Invalidates cached forms of one edited concept and tells subscribers
+    pub(crate) fn concept_changed(&mut self, position: usize) {
+        self.invalidate_matrix();
+        self.index_inserted(position);
+        self.notify(ConceptEvent::Updated(self.concepts[position].clone()));
+    }
+
+    // Removes a concept from the active set, keeping the index keys in step
+    pub(crate) fn take_concept(&mut self, position: usize) -> ConceptVector {
+        let concept = self.concepts.remove(position);
+        self.invalidate_matrix();
+        self.index_removed(position);
+        concept
+    }
+
+    pub(crate) fn notify(&mut self, event: ConceptEvent) {
+        if let Some(registry) = &self.registry {
+            registry.apply(&event);
+        }
+        // Dropped receivers unsubscribe themselves
+        self.subscribers.retain(|subscriber| subscriber.send(event.clone()).is_ok());
+    }
+
+    // Drops every cached form of the concept set: matrix, quantized codes and index
+    pub(crate) fn invalidate_caches(&mut self) {
+        self.invalidate_matrix();
+        *self.index.get_mut().unwrap_or_else(|p| p.into_inner()) = None;
+    }
+
+    fn position_of_id(&self, id: ConceptId) -> Result<usize, SimilarityError> {
+        self.concepts.iter()
+            .position(|c| c.id == id)
+            .ok_or_else(|| SimilarityError::UnknownConcept { name: id.to_string() })
+    }
+
+    fn invalid(&self, id: ConceptId, reason: impl Into<String>) -> SimilarityError {
+        let concept = self.concept_by_id(id).map_or_else(|| id.to_string(), |c| c.name.clone());
+        SimilarityError::InvalidOperation { concept, reason: reason.into() }
+    }
+}
@@ -0,0 +1,88 @@
+// #FF69B4 Similarity Metrics
+use super::cosine_similarity;
+use ndarray::Array1;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+// How a concept vector is compared with an input. Every variant is "higher is closer",
+// so thresholds always mean "at least this similar" in the metric's own units.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SimilarityMetric {
+    #[default]
+    Cosine,       // [-1, 1], ignores magnitude
+    Dot,          // Unbounded, rewards magnitude
+    NegEuclidean, // -‖a − b‖, at most 0
+    Angular,      // 1 − angle/π, in [0, 1]
+    Mahalanobis,  // -distance scaled by the concept's per-dimension variance, at most 0
+}
+
+impl SimilarityMetric {
+    pub const ALL: [SimilarityMetric; 5] = [
+        SimilarityMetric::Cosine,
+        SimilarityMetric::Dot,
+        SimilarityMetric::NegEuclidean,
+        SimilarityMetric::Angular,
+        SimilarityMetric::Mahalanobis,
+    ];
+
+    // `variance` is the concept's diagonal covariance; Mahalanobis without one
+    // degrades to negative Euclidean distance
+    pub fn similarity(&self, concept: &Array1<f32>, input: &Array1<f32>, variance: Option<&Array1<f32>>) -> f32 {
+        match self {
+            SimilarityMetric::Cosine => cosine_similarity(concept, input),
+            SimilarityMetric::Dot => concept.dot(input),
+            SimilarityMetric::NegEuclidean => -(concept - input).mapv(|d| d * d).sum().sqrt(),
+            SimilarityMetric::Angular => {
+                let cosine = cosine_similarity(concept, input).clamp(-1.0, 1.0);
+                1.0 - cosine.acos() / std::f32::consts::PI
+            }
+            SimilarityMetric::Mahalanobis => {
+                let squared = match variance {
+                    Some(variance) => concept.iter().zip(input).zip(variance)
+                        .map(|((c, x), v)| (c - x) * (c - x) / v.max(f32::EPSILON))
+                        .sum::<f32>(),
+                    None => (concept - input).mapv(|d| d * d).sum(),
+                };
+                -squared.sqrt()
+            }
+        }
+    }
+
+    // Metrics whose ranking and thresholds match cosine on normalized rows,
+    // so the cached concept matrix and the HNSW index can serve them
+    pub fn is_cosine(&self) -> bool {
+        matches!(self, SimilarityMetric::Cosine)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SimilarityMetric::Cosine => "cosine",
+            SimilarityMetric::Dot => "dot",
+            SimilarityMetric::NegEuclidean => "neg_euclidean",
+            SimilarityMetric::Angular => "angular",
+            SimilarityMetric::Mahalanobis => "mahalanobis",
+        }
+    }
+}
+
+impl fmt::Display for SimilarityMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for SimilarityMetric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let wanted = s.trim().to_ascii_lowercase().replace('-', "_");
+        Self::ALL.into_iter()
+            .find(|metric| metric.name() == wanted)
+            .ok_or_else(|| format!(
+                "unknown similarity metric '{s}' (expected one of: {})",
+                Self::ALL.map(|m| m.name()).join(", ")
+            ))
+    }
+}
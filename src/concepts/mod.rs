@@ -1,22 +1,113 @@
 // #FF69B4 Vector Similarity Core (Enhanced)
-use ndarray::Array1;
+use crate::embedding::Embedder;
+use crate::embedding::projection::ProjectedEmbedder;
+use ndarray::{Array1, Array2};
 use serde::{Serialize, Deserialize};
+use anyhow::{anyhow, Result};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+pub mod batch;
+pub mod calibration;
+pub mod discovery;
+pub mod error;
+pub mod exclusion;
+pub mod explain;
+pub mod hnsw;
+pub mod jitter;
+pub mod learning;
+pub mod lifecycle;
+pub mod metric;
+pub mod pack;
+pub mod prototypes;
+pub mod quantization;
+pub mod registry;
+pub mod scan;
+pub mod seed;
+pub mod taxonomy;
+
+pub use batch::BatchMatch;
+pub use calibration::{CalibrationObjective, CalibrationReport, LabelledExample};
+pub use discovery::{ClusteringMethod, ConceptDiscovery, ConceptProposal, DiscoveryConfig};
+pub use error::SimilarityError;
+pub use exclusion::{Exclusion, Veto, VetoAction, VetoSource};
+pub use explain::{MatchExplanation, MatchResult};
+pub use hnsw::{HnswConfig, HnswIndex};
+pub use jitter::ThresholdJitter;
+pub use learning::{DriftStats, LearnedState, LearningConfig};
+pub use lifecycle::{ConceptEvent, ConceptId};
+pub use metric::SimilarityMetric;
+pub use pack::{ConceptDefinition, ConceptPack, ExclusionDefinition, LearnedDefinition, PackError};
+pub use prototypes::PrototypeAggregation;
+pub use quantization::{Quantization, QuantizationConfig, QuantizedStore};
+pub use registry::ConceptRegistry;
+pub use seed::{PrototypeStrategy, SeedPrototype};
+pub use taxonomy::HierarchicalMatch;
+
 // Represents a named concept vector for comparison
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct ConceptVector {
+    #[serde(default, skip_serializing_if = "ConceptId::is_unassigned")]
+    pub id: ConceptId,               // Assigned by the engine that holds the concept
     pub name: String,
     pub vector: Array1<f32>,
     pub stochastic_state: [f32; 2], // #7B68EE Stochastic state for non-determinism
     pub threshold: f32,
     pub last_interaction_time: u64,  // Track recency for state updates
     pub curiosity_score: f32,        // Internal curiosity metric
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub seed_texts: Vec<String>,     // Example phrases the vector was derived from
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub negative_texts: Vec<String>, // Phrases that should not match, used to place the threshold
+    #[serde(default)]
+    pub prototype: PrototypeStrategy,
+    #[serde(default)]
+    pub auto_threshold: bool,        // Threshold was suggested from seeds and may be re-derived
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metric: Option<SimilarityMetric>,    // Overrides the engine metric; the threshold is in its units
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub covariance: Option<Array1<f32>>,     // Per-dimension variances for the Mahalanobis metric: diagonal only, no cross terms
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prototypes: Vec<Array1<f32>>,        // Exemplars scored instead of `vector` when present
+    #[serde(default)]
+    pub aggregation: PrototypeAggregation,   // How exemplar scores combine
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub learned: Option<LearnedState>,       // Original vector and momentum once online learning ran
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jitter: Option<ThresholdJitter>,     // Lets the threshold vary with the stochastic state
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,              // Name of the broader concept this one refines
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclusion: Option<Exclusion>,        // Negatives and veto concepts that rule a match out
 }
 
 // Manages and searches concept vectors
 pub struct SimilarityEngine {
-    pub concepts: Vec<ConceptVector>,
+    concepts: Vec<ConceptVector>,                     // Active concepts; change them through the lifecycle API
+    embedder: Option<Box<dyn Embedder>>,
+    dimension: Option<usize>,
+    concept_matrix: RwLock<Option<(u64, Arc<Array2<f32>>)>>, // Row-normalized copy of concept vectors, tagged with its generation
+    generation: u64,                                  // Bumped whenever concept vectors change
+    index: RwLock<Option<HnswIndex>>,                 // Built lazily once `index_config` applies
+    index_config: Option<HnswConfig>,
+    quantized: RwLock<Option<QuantizedStore>>,        // Compressed first-pass copy, built lazily
+    quantization: Option<QuantizationConfig>,
+    metric: SimilarityMetric,                         // Used by concepts without their own metric
+    learning: Option<LearningConfig>,                 // Online learning from confirmed matches, if enabled
+    jitter_rng: Mutex<StdRng>,
+    jitter_enabled: bool,                             // Off for reproducible runs
+    next_id: u64,
+    retired: Vec<ConceptVector>,                      // Soft-retired: kept for `restore_concept`, never matched
+    history: HashMap<ConceptId, VecDeque<Array1<f32>>>, // Recent matched inputs, the material for `split_concept`
+    subscribers: Vec<Sender<ConceptEvent>>,
+    registry: Option<ConceptRegistry>,                // Shared copy of the active concepts, once requested
+    veto_sources: Vec<ConceptVector>,                 // Veto concepts held by another engine, for views
 }
 
 impl SimilarityEngine {
@@ -26,49 +117,327 @@ impl SimilarityEngine {
             .unwrap()
             .as_secs();
 
+        Self::from_concepts(vec![
+            ConceptVector {
+                name: "Curiosity".to_string(),
+                vector: Array1::from_vec(vec![0.9, -0.2, 0.5]),
+                stochastic_state: [1.0, 0.0],
+                threshold: 0.7,
+                last_interaction_time: now,
+                curiosity_score: 0.85,
+                ..ConceptVector::default()
+            },
+            ConceptVector {
+                name: "Aesthetics".to_string(),
+                vector: Array1::from_vec(vec![0.2, 0.8, -0.1]),
+                stochastic_state: [1.0, 0.0],
+                threshold: 0.65,
+                last_interaction_time: now,
+                curiosity_score: 0.75,
+                ..ConceptVector::default()
+            },
+            ConceptVector {
+                name: "Verification".to_string(),
+                vector: Array1::from_vec(vec![-0.3, 0.1, 0.9]),
+                stochastic_state: [1.0, 0.0],
+                threshold: 0.75,
+                last_interaction_time: now,
+                curiosity_score: 0.65,
+                ..ConceptVector::default()
+            },
+        ])
+    }
+
+    // Creates an engine over an explicit set of concepts.
+    // The dimension is taken from the first concept; use `try_from_concepts` to validate the rest.
+    pub fn from_concepts(concepts: Vec<ConceptVector>) -> Self {
+        let dimension = concepts.first().map(|c| c.vector.len());
+        let mut engine = SimilarityEngine {
+            concepts,
+            embedder: None,
+            dimension,
+            concept_matrix: RwLock::new(None),
+            generation: 0,
+            index: RwLock::new(None),
+            index_config: None,
+            quantized: RwLock::new(None),
+            quantization: None,
+            metric: SimilarityMetric::default(),
+            learning: None,
+            jitter_rng: Mutex::new(StdRng::from_entropy()),
+            jitter_enabled: true,
+            next_id: 0,
+            retired: Vec::new(),
+            history: HashMap::new(),
+            subscribers: Vec::new(),
+            registry: None,
+            veto_sources: Vec::new(),
+        };
+        engine.assign_ids();
+        engine
+    }
+
+    // Like `from_concepts`, but rejects concepts whose sizes disagree
+    pub fn try_from_concepts(concepts: Vec<ConceptVector>) -> Result<Self, SimilarityError> {
+        let engine = Self::from_concepts(concepts);
+        engine.validate()?;
+        Ok(engine)
+    }
+
+    // Creates an empty engine that only accepts vectors of the given size
+    pub fn with_dimension(dimension: usize) -> Self {
         SimilarityEngine {
-            concepts: vec![
-                ConceptVector {
-                    name: "Curiosity".to_string(),
-                    vector: Array1::from_vec(vec![0.9, -0.2, 0.5]),
-                    stochastic_state: [1.0, 0.0],
-                    threshold: 0.7,
-                    last_interaction_time: now,
-                    curiosity_score: 0.85,
-                },
-                ConceptVector {
-                    name: "Aesthetics".to_string(),
-                    vector: Array1::from_vec(vec![0.2, 0.8, -0.1]),
-                    stochastic_state: [1.0, 0.0],
-                    threshold: 0.65,
-                    last_interaction_time: now,
-                    curiosity_score: 0.75,
-                },
-                ConceptVector {
-                    name: "Verification".to_string(),
-                    vector: Array1::from_vec(vec![-0.3, 0.1, 0.9]),
-                    stochastic_state: [1.0, 0.0],
-                    threshold: 0.75,
-                    last_interaction_time: now,
-                    curiosity_score: 0.65,
-                },
-            ]
+            dimension: Some(dimension),
+            ..Self::from_concepts(Vec::new())
+        }
+    }
+
+    // Embedding dimension this engine compares in, if known yet
+    pub fn dimension(&self) -> Option<usize> {
+        self.dimension.or_else(|| self.concepts.first().map(|c| c.vector.len()))
+    }
+
+    pub fn concepts(&self) -> &[ConceptVector] {
+        &self.concepts
+    }
+
+    // Checks that every stored concept matches the engine dimension, that names are
+    // unique and that parent links resolve
+    pub fn validate(&self) -> Result<(), SimilarityError> {
+        let mut names = std::collections::HashSet::new();
+        for concept in &self.concepts {
+            self.check_concept(concept)?;
+            if !names.insert(concept.name.as_str()) {
+                return Err(SimilarityError::DuplicateConcept { name: concept.name.clone() });
+            }
+            self.check_exclusion(concept)?;
+        }
+        self.validate_taxonomy()
+    }
+
+    // Adds a concept after checking its size and name against the engine; parents and veto
+    // concepts must be added first. Keeps the concept's id when it is assigned and free.
+    pub fn add_concept(&mut self, mut concept: ConceptVector) -> Result<ConceptId, SimilarityError> {
+        self.check_concept(&concept)?;
+        self.check_unique_name(&concept.name)?;
+        if let Some(parent) = concept.parent.as_deref().filter(|p| self.concept(p).is_none()) {
+            return Err(SimilarityError::InvalidTaxonomy {
+                concept: concept.name.clone(),
+                reason: format!("parent '{parent}' does not exist"),
+            });
+        }
+        self.check_exclusion(&concept)?;
+        if self.dimension.is_none() {
+            self.dimension = Some(concept.vector.len());
+        }
+        if !concept.id.is_assigned() || self.id_taken(concept.id) {
+            concept.id = self.fresh_id();
+        } else {
+            self.next_id = self.next_id.max(concept.id.0);
         }
+        let id = concept.id;
+        self.concepts.push(concept);
+        self.invalidate_matrix();
+        self.index_inserted(self.concepts.len() - 1);
+        self.notify(ConceptEvent::Added(self.concepts[self.concepts.len() - 1].clone()));
+        Ok(id)
     }
 
-    // Finds the concept with the highest cosine similarity above a given threshold
+    // Deletes a concept for good, keeping later concepts in order; see `retire_concept`
+    // for a reversible alternative. Rules that used it as a veto lose that veto.
+    pub fn remove_concept(&mut self, name: &str) -> Option<ConceptVector> {
+        let position = self.concepts.iter().position(|c| c.name == name)?;
+        let removed = self.take_concept(position);
+        self.history.remove(&removed.id);
+        self.notify(ConceptEvent::Removed(removed.id));
+        self.drop_vetoes(name);
+        Some(removed)
+    }
+
+    fn check_concept(&self, concept: &ConceptVector) -> Result<(), SimilarityError> {
+        Self::check_concept_shape(concept, self.dimension())
+    }
+
+    // Vector, prototypes and covariance must agree with each other and with `dimension`;
+    // covariance entries divide scores, so each must be a positive number
+    pub(crate) fn check_concept_shape(concept: &ConceptVector, dimension: Option<usize>) -> Result<(), SimilarityError> {
+        let context = format!("concept '{}'", concept.name);
+        check_size(dimension, concept.vector.len(), &context)?;
+        for (i, prototype) in concept.prototypes.iter().enumerate() {
+            if prototype.len() != concept.vector.len() {
+                return Err(SimilarityError::mismatch(
+                    concept.vector.len(), prototype.len(), format!("{context} prototype #{i}")
+                ));
+            }
+        }
+        let Some(covariance) = &concept.covariance else {
+            return Ok(());
+        };
+        if covariance.len() != concept.vector.len() {
+            return Err(SimilarityError::mismatch(concept.vector.len(), covariance.len(), format!("{context} covariance")));
+        }
+        if let Some(position) = covariance.iter().position(|v| !v.is_finite() || *v <= 0.0) {
+            return Err(SimilarityError::InvalidOperation {
+                concept: concept.name.clone(),
+                reason: format!("covariance[{position}] must be a positive number"),
+            });
+        }
+        Ok(())
+    }
+
+    fn check_dimension(&self, found: usize, context: &str) -> Result<(), SimilarityError> {
+        check_size(self.dimension(), found, context)
+    }
+
+    // Attaches an embedding backend so the engine can match raw text
+    pub fn with_embedder<E: Embedder + 'static>(mut self, embedder: E) -> Result<Self, SimilarityError> {
+        self.set_embedder(Box::new(embedder))?;
+        Ok(self)
+    }
+
+    // Rejects embedders whose output size differs from the stored concepts, unless
+    // every concept is defined by seed texts and can simply be re-derived.
+    // An engine that doesn't know its dimension yet adopts the embedder's.
+    pub fn set_embedder(&mut self, embedder: Box<dyn Embedder>) -> Result<(), SimilarityError> {
+        let dimension = embedder.dimension();
+        if !self.all_seeded() {
+            self.check_dimension(dimension, &format!("embedder '{}'", embedder.model_id()))?;
+        }
+        let previous = self.embedder.replace(embedder);
+        if let Err(e) = self.rederive_seeded_concepts() {
+            self.embedder = previous;
+            return Err(e);
+        }
+        self.dimension.get_or_insert(dimension);
+        Ok(())
+    }
+
+    // Attaches an embedder, inserting a seeded random projection when its size
+    // differs from hand-authored concepts. Returns whether a projection was needed.
+    pub fn set_embedder_with_projection(&mut self, embedder: Box<dyn Embedder>, seed: u64) -> Result<bool, SimilarityError> {
+        match self.dimension() {
+            Some(target) if target != embedder.dimension() && !self.all_seeded() => {
+                self.set_embedder(Box::new(ProjectedEmbedder::random(embedder, target, seed)))?;
+                Ok(true)
+            }
+            _ => {
+                self.set_embedder(embedder)?;
+                Ok(false)
+            }
+        }
+    }
+
+    fn all_seeded(&self) -> bool {
+        !self.concepts.is_empty() && self.concepts.iter().all(|c| c.is_seeded())
+    }
+
+    pub fn embedder(&self) -> Option<&dyn Embedder> {
+        self.embedder.as_deref()
+    }
+
+    // Embeds text with the attached backend
+    pub fn embed(&self, text: &str) -> Result<Array1<f32>> {
+        let embedder = self.embedder()
+            .ok_or_else(|| anyhow!("no embedder attached to the similarity engine"))?;
+        embedder.embed(text)
+    }
+
+    // Embeds the text with the attached backend and finds its best match
+    pub fn find_best_match_text(&self, text: &str) -> Result<Option<ConceptVector>> {
+        let input_vec = self.embed(text)?;
+        Ok(self.try_find_best_match(&input_vec)?)
+    }
+
+    // Finds the concept with the highest cosine similarity above a given threshold.
+    // Mismatched input sizes yield `None`; use `try_find_best_match` to see why.
     pub fn find_best_match(&self, input_vec: &Array1<f32>) -> Option<ConceptVector> {
-        self.concepts.iter()
-            .filter(|cv| cosine_similarity(&cv.vector, input_vec) > cv.threshold)
-            .max_by(|a, b| {
-                cosine_similarity(&a.vector, input_vec)
-                    .partial_cmp(&cosine_similarity(&b.vector, input_vec))
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            })
-            .cloned()
+        self.try_find_best_match(input_vec).ok().flatten()
+    }
+
+    // Same as `find_best_match`, but reports dimension problems as errors
+    pub fn try_find_best_match(&self, input_vec: &Array1<f32>) -> Result<Option<ConceptVector>, SimilarityError> {
+        self.check_dimension(input_vec.len(), "query")?;
+
+        // Large sets only rank the index's candidate list; small ones are scanned exactly.
+        // Plain cosine sets take one pass over the pre-normalized matrix.
+        let candidates: Vec<(usize, f32)> = if self.uses_index() {
+            let ef = self.index_config.unwrap_or_default().ef_search;
+            self.index_search(input_vec, ef)?
+        } else if self.uses_quantization() {
+            self.quantized_search(input_vec, 1)?
+        } else if self.matrix_compatible() && !self.concepts.is_empty() {
+            self.scan_scores(input_vec)?.into_iter().enumerate().collect()
+        } else {
+            let mut scores = Vec::with_capacity(self.concepts.len());
+            for (i, concept) in self.concepts.iter().enumerate() {
+                scores.push((i, self.score(concept, input_vec)?));
+            }
+            scores
+        };
+        let thresholds = self.draw_thresholds();
+        Ok(self.best_candidate(input_vec, candidates, &thresholds)?.map(|(i, _)| self.concepts[i].clone()))
+    }
+
+    // Metric used for concepts that don't choose their own
+    pub fn with_metric(mut self, metric: SimilarityMetric) -> Self {
+        self.set_metric(metric);
+        self
+    }
+
+    // Seeded thresholds move to the new units on the next `rederive_seeded_concepts`
+    pub fn set_metric(&mut self, metric: SimilarityMetric) {
+        self.metric = metric;
+        self.refresh_concept_matrix();
+    }
+
+    pub fn metric(&self) -> SimilarityMetric {
+        self.metric
+    }
+
+    // The metric a concept is actually scored with
+    pub fn metric_for(&self, concept: &ConceptVector) -> SimilarityMetric {
+        concept.metric.unwrap_or(self.metric)
+    }
+
+    // True when concepts are scored in different units, so raw scores can't be compared
+    pub(crate) fn mixed_metrics(&self) -> bool {
+        let mut metrics = self.concepts.iter().map(|c| self.metric_for(c));
+        metrics.next().is_some_and(|first| metrics.any(|m| m != first))
+    }
+
+    // What candidates are ranked by: the raw score when every concept shares a metric,
+    // otherwise the margin over the concept's threshold, which means the same in any units
+    pub(crate) fn rank_score(similarity: f32, threshold: f32, mixed: bool) -> f32 {
+        if mixed { similarity - threshold } else { similarity }
     }
 
-    // Updates concept after interaction
+    // True when every concept is a single vector scored by cosine, so the
+    // cached matrix and the index can stand in for `score`
+    pub(crate) fn matrix_compatible(&self) -> bool {
+        self.concepts.iter().all(|c| self.metric_for(c).is_cosine() && !c.is_multi_prototype())
+    }
+
+    // Similarity of one concept to the input; every matching path goes through here
+    pub(crate) fn score(&self, concept: &ConceptVector, input_vec: &Array1<f32>) -> Result<f32, SimilarityError> {
+        self.score_with_prototype(concept, input_vec).map(|(similarity, _)| similarity)
+    }
+
+    // Aggregated similarity plus the index of the prototype that fired
+    pub(crate) fn score_with_prototype(&self, concept: &ConceptVector, input_vec: &Array1<f32>) -> Result<(f32, usize), SimilarityError> {
+        let metric = self.metric_for(concept);
+        let mut scores = Vec::with_capacity(concept.prototypes.len().max(1));
+        for prototype in concept.prototype_vectors() {
+            if prototype.len() != input_vec.len() {
+                return Err(SimilarityError::mismatch(
+                    prototype.len(), input_vec.len(), format!("concept '{}'", concept.name)
+                ));
+            }
+            scores.push(metric.similarity(prototype, input_vec, concept.covariance.as_ref()));
+        }
+        Ok(concept.aggregation.aggregate(&scores))
+    }
+
+    // Updates concept after interaction; vectors only move through `confirm_match`
     pub fn update_concept_after_interaction(&mut self, name: &str) {
         if let Some(concept) = self.concepts.iter_mut().find(|c| c.name == name) {
             let now = SystemTime::now()
@@ -76,10 +445,17 @@ impl SimilarityEngine {
                 .unwrap()
                 .as_secs();
             concept.last_interaction_time = now;
+            let updated = concept.clone();
+            // Only a timestamp changed: views are patched rather than rebuilt
+            if let Some(registry) = &self.registry {
+                registry.touch(&updated);
+            }
+            self.subscribers.retain(|subscriber| subscriber.send(ConceptEvent::Updated(updated.clone())).is_ok());
         }
     }
 }
 
+// Panics if the lengths differ; prefer `try_cosine_similarity` for untrusted input
 pub fn cosine_similarity(a: &Array1<f32>, b: &Array1<f32>) -> f32 {
     let dot_product = a.dot(b);
     let norm_a = a.dot(a).sqrt();
@@ -90,6 +466,25 @@ pub fn cosine_similarity(a: &Array1<f32>, b: &Array1<f32>) -> f32 {
     dot_product / (norm_a * norm_b)
 }
 
+// Rejects empty vectors, and sizes other than `expected` once one is known
+fn check_size(expected: Option<usize>, found: usize, context: &str) -> Result<(), SimilarityError> {
+    if found == 0 {
+        return Err(SimilarityError::EmptyVector { context: context.to_string() });
+    }
+    match expected {
+        Some(expected) if expected != found => Err(SimilarityError::mismatch(expected, found, context)),
+        _ => Ok(()),
+    }
+}
+
+// Length-checked cosine similarity
+pub fn try_cosine_similarity(a: &Array1<f32>, b: &Array1<f32>, context: &str) -> Result<f32, SimilarityError> {
+    if a.len() != b.len() {
+        return Err(SimilarityError::mismatch(a.len(), b.len(), context));
+    }
+    Ok(cosine_similarity(a, b))
+}
+
 // Default implementation for ConceptVector
 impl Default for ConceptVector {
     fn default() -> Self {
@@ -99,12 +494,26 @@ impl Default for ConceptVector {
             .as_secs();
 
         ConceptVector {
+            id: ConceptId::default(),
             name: "Default".to_string(),
             vector: Array1::from_vec(vec![0.0, 0.0, 0.0]),
             stochastic_state: [0.5, 0.5],
             threshold: 0.5,
             last_interaction_time: now,
             curiosity_score: 0.5,
+            seed_texts: Vec::new(),
+            negative_texts: Vec::new(),
+            prototype: PrototypeStrategy::default(),
+            auto_threshold: false,
+            metadata: BTreeMap::new(),
+            metric: None,
+            covariance: None,
+            prototypes: Vec::new(),
+            aggregation: PrototypeAggregation::default(),
+            learned: None,
+            jitter: None,
+            parent: None,
+            exclusion: None,
         }
     }
 }
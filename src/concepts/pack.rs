@@ -0,0 +1,518 @@
+// #FF69B4 Concept Packs (JSON / TOML)
+use super::prototypes::centroid;
+use super::seed::{derive_prototype, PrototypeStrategy, SeedPrototype};
+use super::exclusion::DEFAULT_VETO_MARGIN;
+use super::{
+    ConceptVector, Exclusion, LearnedState, PrototypeAggregation, SimilarityEngine, SimilarityMetric, ThresholdJitter, VetoAction,
+};
+use crate::embedding::Embedder;
+use ndarray::Array1;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Threshold used when a definition leaves it out (the manifest's activation condition)
+pub const DEFAULT_THRESHOLD: f32 = 0.7;
+
+// On-disk collection of concept definitions
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ConceptPack {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dimension: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metric: Option<SimilarityMetric>, // Engine default; concepts may override it
+    #[serde(default)]
+    pub concepts: Vec<ConceptDefinition>,
+}
+
+// One concept as authored by a person: either a raw vector or example phrases
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ConceptDefinition {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vector: Option<Vec<f32>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub seed_texts: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub negative_texts: Vec<String>,
+    #[serde(default)]
+    pub prototype: PrototypeStrategy,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f32>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub auto_threshold: bool, // Threshold is a seed suggestion and is recomputed on load
+    #[serde(default = "default_curiosity")]
+    pub curiosity_score: f32,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metric: Option<SimilarityMetric>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub covariance: Option<Vec<f32>>, // Diagonal; re-derived whenever seed texts are embedded
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prototypes: Vec<Vec<f32>>,    // Several exemplars for broad concepts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aggregation: Option<PrototypeAggregation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jitter: Option<ThresholdJitter>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>, // Broader concept; supplies the threshold when none is given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclusion: Option<ExclusionDefinition>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub learned: Option<LearnedDefinition>,
+}
+
+// Where online learning started from, so `max_drift` stays measured from the original
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LearnedDefinition {
+    pub original: Vec<f32>,
+    #[serde(default)]
+    pub updates: u64,
+}
+
+// "Match unless…" rule as authored: veto concepts by name, negative phrases and raw vectors
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ExclusionDefinition {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub vetoes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub negatives: Vec<Vec<f32>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub texts: Vec<String>,              // Embedded when an embedder is available
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub text_negatives: Vec<Vec<f32>>,   // Stored embeddings of `texts`, used without an embedder
+    #[serde(default = "default_veto_margin")]
+    pub margin: f32,
+    #[serde(default)]
+    pub action: VetoAction,
+}
+
+fn default_curiosity() -> f32 {
+    0.5
+}
+
+fn default_veto_margin() -> f32 {
+    DEFAULT_VETO_MARGIN
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PackFormat {
+    Json,
+    Toml,
+}
+
+impl PackFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "json" => Some(PackFormat::Json),
+            "toml" => Some(PackFormat::Toml),
+            _ => None,
+        }
+    }
+}
+
+// Errors raised while reading, writing or validating a concept pack
+#[derive(Debug, Clone, PartialEq)]
+pub enum PackError {
+    Io { path: PathBuf, message: String },
+    Parse { path: PathBuf, message: String },
+    UnsupportedFormat { path: PathBuf },
+    Embedder { message: String },
+    InvalidEntry { index: usize, name: String, reason: String },
+}
+
+impl PackError {
+    fn entry(index: usize, name: &str, reason: impl Into<String>) -> Self {
+        PackError::InvalidEntry { index, name: name.to_string(), reason: reason.into() }
+    }
+}
+
+impl fmt::Display for PackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PackError::Io { path, message } => write!(f, "{}: {message}", path.display()),
+            PackError::Parse { path, message } => write!(f, "failed to parse {}: {message}", path.display()),
+            PackError::UnsupportedFormat { path } => {
+                write!(f, "{}: concept packs must be .json or .toml", path.display())
+            }
+            PackError::Embedder { message } => write!(f, "embedder does not fit the pack: {message}"),
+            PackError::InvalidEntry { index, name, reason } => {
+                write!(f, "concept #{index} ('{name}'): {reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PackError {}
+
+impl ConceptPack {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, PackError> {
+        let path = path.as_ref();
+        let format = PackFormat::from_path(path)
+            .ok_or_else(|| PackError::UnsupportedFormat { path: path.to_path_buf() })?;
+        let text = fs::read_to_string(path)
+            .map_err(|e| PackError::Io { path: path.to_path_buf(), message: e.to_string() })?;
+        let parsed = match format {
+            PackFormat::Json => serde_json::from_str(&text).map_err(|e| e.to_string()),
+            PackFormat::Toml => toml::from_str(&text).map_err(|e| e.to_string()),
+        };
+        parsed.map_err(|message| PackError::Parse { path: path.to_path_buf(), message })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), PackError> {
+        let path = path.as_ref();
+        let format = PackFormat::from_path(path)
+            .ok_or_else(|| PackError::UnsupportedFormat { path: path.to_path_buf() })?;
+        let text = match format {
+            PackFormat::Json => serde_json::to_string_pretty(self).map_err(|e| e.to_string()),
+            PackFormat::Toml => toml::to_string_pretty(self).map_err(|e| e.to_string()),
+        }
+        .map_err(|message| PackError::Parse { path: path.to_path_buf(), message })?;
+        fs::write(path, text).map_err(|e| PackError::Io { path: path.to_path_buf(), message: e.to_string() })
+    }
+
+    // Checks every entry and turns it into a concept; seed texts need an embedder.
+    // The stored `dimension` describes the saved vectors, so once an embedder re-derives
+    // seeded entries, every entry has to fit the embedder instead.
+    pub fn to_concepts(&self, embedder: Option<&dyn Embedder>) -> Result<Vec<ConceptVector>, PackError> {
+        let mut names = HashSet::new();
+        let mut dimension = match embedder {
+            Some(embedder) if self.concepts.iter().any(|d| !d.seed_texts.is_empty()) => Some(embedder.dimension()),
+            _ => self.dimension,
+        };
+        let mut concepts = Vec::with_capacity(self.concepts.len());
+        let mut inherits_threshold = Vec::with_capacity(self.concepts.len());
+
+        for (index, definition) in self.concepts.iter().enumerate() {
+            let name = definition.name.trim();
+            if name.is_empty() {
+                return Err(PackError::entry(index, &definition.name, "name must not be empty"));
+            }
+            if !names.insert(name.to_string()) {
+                return Err(PackError::entry(index, name, "duplicate concept name"));
+            }
+
+            if definition.threshold.is_some_and(|t| !t.is_finite()) {
+                return Err(PackError::entry(index, name, "threshold must be a finite number"));
+            }
+            if definition.jitter.is_some_and(|j| !j.amplitude.is_finite() || j.amplitude < 0.0) {
+                return Err(PackError::entry(index, name, "jitter amplitude must be a non-negative number"));
+            }
+            if !(0.0..=1.0).contains(&definition.curiosity_score) {
+                return Err(PackError::entry(
+                    index, name,
+                    format!("curiosity_score {} is outside [0, 1]", definition.curiosity_score),
+                ));
+            }
+
+            let metric = definition.metric.or(self.metric).unwrap_or_default();
+            let prototypes = definition_prototypes(index, name, &definition.prototypes)?;
+            let (vector, seeded) = definition_vector(index, name, definition, embedder, metric, &prototypes)?;
+            let suggested_threshold = seeded.as_ref().map(|p| p.suggested_threshold);
+            let auto_threshold = !definition.seed_texts.is_empty()
+                && (definition.auto_threshold || definition.threshold.is_none());
+            match dimension {
+                Some(expected) if expected != vector.len() => {
+                    return Err(PackError::entry(
+                        index, name,
+                        format!("vector has {} dimensions, expected {expected}", vector.len()),
+                    ));
+                }
+                _ => dimension = Some(vector.len()),
+            }
+            if let Some(i) = prototypes.iter().position(|p| p.len() != vector.len()) {
+                return Err(PackError::entry(
+                    index, name,
+                    format!("prototype #{i} has {} dimensions, expected {}", prototypes[i].len(), vector.len()),
+                ));
+            }
+            // Re-derived vectors start over, like `rederive_seeded_concepts`
+            let learned = match (&definition.learned, &seeded) {
+                (Some(learned), None) => Some(definition_learned(index, name, learned, vector.len())?),
+                _ => None,
+            };
+            // Like the vector, a stored covariance gives way to one derived from the seeds
+            let covariance = match (&definition.covariance, seeded) {
+                (_, Some(prototype)) => Some(prototype.covariance),
+                (Some(values), None) => Some(definition_covariance(index, name, values, vector.len())?),
+                (None, None) => None,
+            };
+            let exclusion = match &definition.exclusion {
+                Some(exclusion) => Some(definition_exclusion(index, name, exclusion, embedder, vector.len())?),
+                None => None,
+            };
+
+            inherits_threshold.push(definition.threshold.is_none() && suggested_threshold.is_none());
+            concepts.push(ConceptVector {
+                name: name.to_string(),
+                vector,
+                threshold: match (auto_threshold, suggested_threshold) {
+                    (true, Some(suggested)) => suggested,
+                    _ => definition.threshold.or(suggested_threshold).unwrap_or(DEFAULT_THRESHOLD),
+                },
+                curiosity_score: definition.curiosity_score,
+                seed_texts: definition.seed_texts.clone(),
+                negative_texts: definition.negative_texts.clone(),
+                prototype: definition.prototype,
+                auto_threshold,
+                metadata: definition.metadata.clone(),
+                metric: definition.metric,
+                covariance,
+                prototypes,
+                aggregation: definition.aggregation.unwrap_or_default(),
+                jitter: definition.jitter,
+                parent: definition.parent.as_ref().map(|p| p.trim().to_string()),
+                exclusion,
+                learned,
+                ..ConceptVector::default()
+            });
+        }
+        resolve_parents(&mut concepts, &inherits_threshold)?;
+        resolve_vetoes(&concepts)?;
+        Ok(concepts)
+    }
+}
+
+// Checks parent links and copies thresholds down to children that left theirs out
+fn resolve_parents(concepts: &mut [ConceptVector], inherits_threshold: &[bool]) -> Result<(), PackError> {
+    let positions: HashMap<&str, usize> = concepts.iter().enumerate().map(|(i, c)| (c.name.as_str(), i)).collect();
+    let mut parents = vec![None; concepts.len()];
+    for (index, concept) in concepts.iter().enumerate() {
+        if let Some(parent) = &concept.parent {
+            let position = positions.get(parent.as_str())
+                .ok_or_else(|| PackError::entry(index, &concept.name, format!("parent '{parent}' does not exist")))?;
+            parents[index] = Some(*position);
+        }
+    }
+
+    for index in 0..concepts.len() {
+        let (mut current, mut steps) = (index, 0);
+        while let Some(parent) = parents[current] {
+            steps += 1;
+            if steps > concepts.len() {
+                return Err(PackError::entry(index, &concepts[index].name, "parent links form a cycle"));
+            }
+            current = parent;
+        }
+    }
+
+    for index in 0..concepts.len() {
+        let mut source = index;
+        while let (true, Some(parent)) = (inherits_threshold[source], parents[source]) {
+            source = parent;
+        }
+        concepts[index].threshold = concepts[source].threshold;
+    }
+    Ok(())
+}
+
+// Veto concepts must be other entries of the same pack
+fn resolve_vetoes(concepts: &[ConceptVector]) -> Result<(), PackError> {
+    let names: HashSet<&str> = concepts.iter().map(|c| c.name.as_str()).collect();
+    for (index, concept) in concepts.iter().enumerate() {
+        for veto in concept.exclusion.iter().flat_map(|e| &e.vetoes) {
+            if *veto == concept.name {
+                return Err(PackError::entry(index, &concept.name, "a concept cannot veto itself"));
+            }
+            if !names.contains(veto.as_str()) {
+                return Err(PackError::entry(index, &concept.name, format!("veto concept '{veto}' does not exist")));
+            }
+        }
+    }
+    Ok(())
+}
+
+// Seed texts win when an embedder is available, so packs follow embedder changes;
+// the stored vector is used otherwise. Seeds also yield a suggested threshold and covariance.
+fn definition_vector(
+    index: usize,
+    name: &str,
+    definition: &ConceptDefinition,
+    embedder: Option<&dyn Embedder>,
+    metric: SimilarityMetric,
+    prototypes: &[Array1<f32>],
+) -> Result<(Array1<f32>, Option<SeedPrototype>), PackError> {
+    if let (Some(embedder), false) = (embedder, definition.seed_texts.is_empty()) {
+        let prototype = derive_prototype(embedder, &definition.seed_texts, &definition.negative_texts, definition.prototype, metric)
+            .map_err(|e| PackError::entry(index, name, format!("failed to embed seed texts: {e}")))?;
+        return Ok((prototype.vector.clone(), Some(prototype)));
+    }
+
+    if let Some(values) = &definition.vector {
+        if values.is_empty() {
+            return Err(PackError::entry(index, name, "vector must not be empty"));
+        }
+        if let Some(position) = values.iter().position(|v| !v.is_finite()) {
+            return Err(PackError::entry(index, name, format!("vector[{position}] is not finite")));
+        }
+        return Ok((Array1::from_vec(values.clone()), None));
+    }
+
+    if !prototypes.is_empty() {
+        return Ok((centroid(prototypes), None));
+    }
+
+    if definition.seed_texts.is_empty() {
+        Err(PackError::entry(index, name, "needs a vector, prototypes or seed_texts"))
+    } else {
+        Err(PackError::entry(index, name, "seed_texts without a vector require an embedder"))
+    }
+}
+
+fn definition_prototypes(index: usize, name: &str, prototypes: &[Vec<f32>]) -> Result<Vec<Array1<f32>>, PackError> {
+    let mut parsed = Vec::with_capacity(prototypes.len());
+    for (i, values) in prototypes.iter().enumerate() {
+        if values.is_empty() || values.iter().any(|v| !v.is_finite()) {
+            return Err(PackError::entry(index, name, format!("prototype #{i} must be a non-empty list of finite numbers")));
+        }
+        parsed.push(Array1::from_vec(values.clone()));
+    }
+    Ok(parsed)
+}
+
+fn definition_covariance(index: usize, name: &str, values: &[f32], dimension: usize) -> Result<Array1<f32>, PackError> {
+    if values.len() != dimension {
+        return Err(PackError::entry(
+            index, name,
+            format!("covariance has {} entries, expected {dimension}", values.len()),
+        ));
+    }
+    if let Some(position) = values.iter().position(|v| !v.is_finite() || *v <= 0.0) {
+        return Err(PackError::entry(index, name, format!("covariance[{position}] must be a positive number")));
+    }
+    Ok(Array1::from_vec(values.to_vec()))
+}
+
+fn definition_learned(index: usize, name: &str, definition: &LearnedDefinition, dimension: usize) -> Result<LearnedState, PackError> {
+    if definition.original.len() != dimension || definition.original.iter().any(|v| !v.is_finite()) {
+        return Err(PackError::entry(index, name, format!("learned original must be {dimension} finite numbers")));
+    }
+    Ok(LearnedState {
+        original: Array1::from_vec(definition.original.clone()),
+        velocity: Array1::zeros(dimension), // Momentum restarts after a reload
+        updates: definition.updates,
+    })
+}
+
+fn definition_exclusion(
+    index: usize,
+    name: &str,
+    definition: &ExclusionDefinition,
+    embedder: Option<&dyn Embedder>,
+    dimension: usize,
+) -> Result<Exclusion, PackError> {
+    if !definition.margin.is_finite() {
+        return Err(PackError::entry(index, name, "veto margin must be a finite number"));
+    }
+    if let VetoAction::Penalize(penalty) = definition.action {
+        if !penalty.is_finite() || penalty < 0.0 {
+            return Err(PackError::entry(index, name, "veto penalty must be a non-negative number"));
+        }
+    }
+    let mut negatives = Vec::with_capacity(definition.negatives.len());
+    for (i, values) in definition.negatives.iter().enumerate() {
+        if values.len() != dimension || values.iter().any(|v| !v.is_finite()) {
+            return Err(PackError::entry(index, name, format!("negative #{i} must be {dimension} finite numbers")));
+        }
+        negatives.push(Array1::from_vec(values.clone()));
+    }
+
+    // Phrases follow the embedder like seed texts; stored embeddings stand in without one
+    let text_negatives: Vec<Array1<f32>> = match embedder {
+        Some(embedder) if !definition.texts.is_empty() => {
+            let texts: Vec<&str> = definition.texts.iter().map(String::as_str).collect();
+            let embedded = embedder.embed_batch(&texts)
+                .map_err(|e| PackError::entry(index, name, format!("failed to embed negative texts: {e}")))?;
+            embedded.rows().into_iter().map(|r| r.to_owned()).collect()
+        }
+        _ if definition.text_negatives.len() != definition.texts.len() => {
+            return Err(PackError::entry(index, name, "negative texts without stored vectors require an embedder"));
+        }
+        _ => definition.text_negatives.iter().map(|v| Array1::from_vec(v.clone())).collect(),
+    };
+    if let Some(i) = text_negatives.iter().position(|n| n.len() != dimension || n.iter().any(|v| !v.is_finite())) {
+        return Err(PackError::entry(index, name, format!("negative text #{i} must embed to {dimension} finite numbers")));
+    }
+
+    Ok(Exclusion {
+        negatives,
+        texts: definition.texts.clone(),
+        text_negatives,
+        vetoes: definition.vetoes.iter().map(|v| v.trim().to_string()).collect(),
+        margin: definition.margin,
+        action: definition.action,
+    })
+}
+
+impl SimilarityEngine {
+    // Loads a JSON or TOML concept pack containing explicit vectors
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, PackError> {
+        Self::from_pack(&ConceptPack::load(path)?, None)
+    }
+
+    // Loads a pack, embedding any seed texts with `embedder`, and attaches the embedder
+    pub fn from_path_with_embedder(path: impl AsRef<Path>, embedder: Box<dyn Embedder>) -> Result<Self, PackError> {
+        let pack = ConceptPack::load(path)?;
+        let mut engine = Self::from_pack(&pack, Some(embedder.as_ref()))?;
+        if engine.dimension.is_none() {
+            engine.dimension = Some(embedder.dimension());
+        }
+        engine.set_embedder(embedder).map_err(|e| PackError::Embedder { message: e.to_string() })?;
+        Ok(engine)
+    }
+
+    pub fn from_pack(pack: &ConceptPack, embedder: Option<&dyn Embedder>) -> Result<Self, PackError> {
+        let mut engine = Self::from_concepts(pack.to_concepts(embedder)?);
+        if engine.dimension.is_none() {
+            engine.dimension = pack.dimension;
+        }
+        engine.metric = pack.metric.unwrap_or_default();
+        Ok(engine)
+    }
+
+    pub fn to_pack(&self) -> ConceptPack {
+        ConceptPack {
+            dimension: self.dimension(),
+            metric: Some(self.metric),
+            concepts: self.concepts.iter()
+                .map(|concept| ConceptDefinition {
+                    name: concept.name.clone(),
+                    vector: Some(concept.vector.to_vec()),
+                    seed_texts: concept.seed_texts.clone(),
+                    negative_texts: concept.negative_texts.clone(),
+                    prototype: concept.prototype,
+                    threshold: Some(concept.threshold),
+                    auto_threshold: concept.auto_threshold,
+                    curiosity_score: concept.curiosity_score,
+                    metadata: concept.metadata.clone(),
+                    metric: concept.metric,
+                    covariance: concept.covariance.as_ref().map(|c| c.to_vec()),
+                    prototypes: concept.prototypes.iter().map(|p| p.to_vec()).collect(),
+                    aggregation: concept.is_multi_prototype().then_some(concept.aggregation),
+                    jitter: concept.jitter,
+                    parent: concept.parent.clone(),
+                    exclusion: concept.exclusion.as_ref().map(|exclusion| ExclusionDefinition {
+                        vetoes: exclusion.vetoes.clone(),
+                        negatives: exclusion.negatives.iter().map(|n| n.to_vec()).collect(),
+                        texts: exclusion.texts.clone(),
+                        text_negatives: exclusion.text_negatives.iter().map(|n| n.to_vec()).collect(),
+                        margin: exclusion.margin,
+                        action: exclusion.action,
+                    }),
+                    learned: concept.learned.as_ref().map(|learned| LearnedDefinition {
+                        original: learned.original.to_vec(),
+                        updates: learned.updates,
+                    }),
+                })
+                .collect(),
+        }
+    }
+
+    // Writes the current concepts as a pack; the format follows the file extension
+    pub fn to_path(&self, path: impl AsRef<Path>) -> Result<(), PackError> {
+        self.to_pack().save(path)
+    }
+}
@@ -0,0 +1,92 @@
+// #FF69B4 Multi-Prototype Concepts
+use super::ConceptVector;
+use ndarray::Array1;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// How the scores of a concept's prototypes combine into one similarity
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case", tag = "policy")]
+pub enum PrototypeAggregation {
+    #[default]
+    Max,                          // Closest prototype wins
+    Mean,                         // Average over all prototypes
+    SoftMax { temperature: f32 }, // Softmax-weighted mean; low temperatures approach `Max`
+}
+
+impl PrototypeAggregation {
+    // Combined score plus the index of the prototype that contributed most
+    pub fn aggregate(&self, scores: &[f32]) -> (f32, usize) {
+        let best = scores.iter().enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map_or((f32::NEG_INFINITY, 0), |(i, &s)| (s, i));
+        if scores.len() <= 1 {
+            return (best.0, best.1);
+        }
+
+        let combined = match *self {
+            PrototypeAggregation::Max => best.0,
+            PrototypeAggregation::Mean => scores.iter().sum::<f32>() / scores.len() as f32,
+            PrototypeAggregation::SoftMax { temperature } if temperature > 0.0 => {
+                // Shift by the maximum so exp() cannot overflow
+                let weights: Vec<f32> = scores.iter().map(|s| ((s - best.0) / temperature).exp()).collect();
+                let total: f32 = weights.iter().sum();
+                scores.iter().zip(&weights).map(|(s, w)| s * w).sum::<f32>() / total
+            }
+            PrototypeAggregation::SoftMax { .. } => best.0,
+        };
+        (combined, best.1)
+    }
+}
+
+impl fmt::Display for PrototypeAggregation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrototypeAggregation::Max => f.write_str("max"),
+            PrototypeAggregation::Mean => f.write_str("mean"),
+            PrototypeAggregation::SoftMax { temperature } => write!(f, "softmax(t={temperature})"),
+        }
+    }
+}
+
+impl ConceptVector {
+    // Replaces the concept's exemplars; `vector` becomes their normalized centroid so
+    // summaries and exports still have a single representative direction
+    pub fn with_prototypes(mut self, prototypes: Vec<Array1<f32>>, aggregation: PrototypeAggregation) -> Self {
+        if !prototypes.is_empty() {
+            self.vector = centroid(&prototypes);
+        }
+        self.prototypes = prototypes;
+        self.aggregation = aggregation;
+        self
+    }
+
+    // Scored exemplars: the explicit prototypes, or just `vector` for single-prototype concepts
+    pub fn prototype_vectors(&self) -> Vec<&Array1<f32>> {
+        if self.prototypes.is_empty() {
+            vec![&self.vector]
+        } else {
+            self.prototypes.iter().collect()
+        }
+    }
+
+    pub fn is_multi_prototype(&self) -> bool {
+        !self.prototypes.is_empty()
+    }
+}
+
+// Normalized mean direction of the prototypes
+pub(crate) fn centroid(prototypes: &[Array1<f32>]) -> Array1<f32> {
+    let mut centroid = Array1::zeros(prototypes.first().map_or(0, |p| p.len()));
+    for prototype in prototypes {
+        let norm = prototype.dot(prototype).sqrt();
+        if norm > 0.0 && prototype.len() == centroid.len() {
+            centroid.scaled_add(1.0 / norm, prototype);
+        }
+    }
+    let norm = centroid.dot(&centroid).sqrt();
+    if norm > 0.0 {
+        centroid.mapv_inplace(|x| x / norm);
+    }
+    centroid
+}
@@ -0,0 +1,366 @@
+// #FF69B4 Vector Quantization (int8 scalar / product quantization)
+use super::{SimilarityEngine, SimilarityError};
+use crate::embedding::projection::normalize_rows;
+use ndarray::{s, Array1, Array2, ArrayView1, ArrayView2, Axis};
+use rand::rngs::StdRng;
+use rand::seq::index::sample;
+use rand::SeedableRng;
+use std::collections::HashSet;
+
+// Product-quantization codebooks are trained on at most this many concepts
+const TRAINING_SAMPLE: usize = 4096;
+
+// Vectors normalized per encoding pass, so only a slice of f32 rows exists at a time
+const ENCODE_CHUNK: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Quantization {
+    Int8,                                             // One byte per dimension plus a scale per concept
+    Product { subspaces: usize, centroids: usize },   // One byte per subspace; `centroids` is at most 256
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuantizationConfig {
+    pub method: Quantization,
+    pub rescore: usize,     // Approximate candidates re-ranked with the full-precision vectors
+    pub exact_below: usize, // Engines with fewer concepts keep using the exact scan
+    pub iterations: usize,  // k-means rounds per product-quantization subspace
+    pub seed: u64,
+}
+
+impl Default for QuantizationConfig {
+    fn default() -> Self {
+        QuantizationConfig {
+            method: Quantization::Int8,
+            rescore: 32,
+            exact_below: 1000,
+            iterations: 10,
+            seed: 0,
+        }
+    }
+}
+
+// Compressed copy of the normalized concept vectors, scored against f32 queries
+// (asymmetric distance computation: only the stored side is quantized)
+#[derive(Debug, Clone)]
+pub struct QuantizedStore {
+    codes: Codes,
+    rows: usize,
+    stale: bool, // Concepts changed; re-encode, keeping any trained codebooks
+}
+
+#[derive(Debug, Clone)]
+enum Codes {
+    Int8 { codes: Array2<i8>, scales: Array1<f32> },
+    Product { codes: Array2<u8>, codebooks: Vec<Array2<f32>>, bounds: Vec<(usize, usize)> },
+}
+
+impl QuantizedStore {
+    // Quantizes equally sized vectors, normalizing them chunk by chunk without keeping an
+    // f32 copy; `previous` lends its product codebooks when they still fit
+    pub fn build(config: &QuantizationConfig, vectors: &[ArrayView1<f32>], previous: Option<&QuantizedStore>) -> Self {
+        let dimension = vectors.first().map_or(0, |v| v.len());
+        let codes = match config.method {
+            Quantization::Int8 => encode_int8(vectors, dimension),
+            Quantization::Product { subspaces, centroids } => {
+                let reusable = previous.and_then(|p| match &p.codes {
+                    Codes::Product { codebooks, bounds, .. }
+                        if bounds.last().map(|b| b.1) == Some(dimension)
+                            && bounds.len() == subspaces.clamp(1, dimension.max(1))
+                            && codebooks.first().is_some_and(|c| c.nrows() <= centroids) =>
+                    {
+                        Some((codebooks.clone(), bounds.clone()))
+                    }
+                    _ => None,
+                });
+                let (codebooks, bounds) = reusable.unwrap_or_else(|| train_codebooks(config, vectors, dimension, subspaces, centroids));
+                let codes = encode_product(vectors, &codebooks, &bounds);
+                Codes::Product { codes, codebooks, bounds }
+            }
+        };
+        QuantizedStore { codes, rows: vectors.len(), stale: false }
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows == 0
+    }
+
+    // Bytes held by codes, scales and codebooks
+    pub fn memory_bytes(&self) -> usize {
+        match &self.codes {
+            Codes::Int8 { codes, scales } => codes.len() + scales.len() * size_of::<f32>(),
+            Codes::Product { codes, codebooks, .. } => {
+                codes.len() + codebooks.iter().map(|c| c.len() * size_of::<f32>()).sum::<usize>()
+            }
+        }
+    }
+
+    // Approximate cosine of a normalized query to every stored row
+    pub fn scores(&self, query: ArrayView1<f32>) -> Array1<f32> {
+        match &self.codes {
+            Codes::Int8 { codes, scales } => {
+                Array1::from_iter(codes.rows().into_iter().zip(scales).map(|(row, scale)| {
+                    row.iter().zip(&query).map(|(&c, &q)| c as f32 * q).sum::<f32>() * scale
+                }))
+            }
+            Codes::Product { codes, codebooks, bounds } => {
+                // One lookup table per subspace, then each row is a sum of table entries
+                let tables: Vec<Array1<f32>> = codebooks.iter().zip(bounds)
+                    .map(|(codebook, &(start, end))| codebook.dot(&query.slice(s![start..end])))
+                    .collect();
+                Array1::from_iter(codes.rows().into_iter().map(|row| {
+                    row.iter().zip(&tables).map(|(&code, table)| table[code as usize]).sum::<f32>()
+                }))
+            }
+        }
+    }
+
+    // Up to `k` (row, approximate score) pairs, best first
+    pub fn search(&self, query: ArrayView1<f32>, k: usize) -> Vec<(usize, f32)> {
+        let mut scored: Vec<(usize, f32)> = self.scores(query).into_iter().enumerate().collect();
+        let k = k.min(scored.len());
+        if k == 0 {
+            return Vec::new();
+        }
+        scored.select_nth_unstable_by(k - 1, |a, b| b.1.total_cmp(&a.1));
+        scored.truncate(k);
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored
+    }
+}
+
+// Unit-length rows of the given vectors
+fn normalized(vectors: &[ArrayView1<f32>], dimension: usize) -> Array2<f32> {
+    let mut rows = Array2::zeros((vectors.len(), dimension));
+    for (mut row, vector) in rows.rows_mut().into_iter().zip(vectors) {
+        row.assign(vector);
+    }
+    normalize_rows(&mut rows);
+    rows
+}
+
+fn encode_int8(vectors: &[ArrayView1<f32>], dimension: usize) -> Codes {
+    let mut codes = Array2::zeros((vectors.len(), dimension));
+    let mut scales = Array1::zeros(vectors.len());
+    for (start, chunk) in (0..).step_by(ENCODE_CHUNK).zip(vectors.chunks(ENCODE_CHUNK)) {
+        let rows = normalized(chunk, dimension);
+        for (i, row) in rows.rows().into_iter().enumerate() {
+            let scale = row.iter().fold(0.0f32, |m, x| m.max(x.abs())) / 127.0;
+            scales[start + i] = scale;
+            if scale > 0.0 {
+                codes.row_mut(start + i).assign(&row.mapv(|x| (x / scale).round().clamp(-127.0, 127.0) as i8));
+            }
+        }
+    }
+    Codes::Int8 { codes, scales }
+}
+
+// Near-equal contiguous dimension ranges, one per subspace
+fn subspace_bounds(dimension: usize, subspaces: usize) -> Vec<(usize, usize)> {
+    let subspaces = subspaces.clamp(1, dimension.max(1));
+    let (base, extra) = (dimension / subspaces, dimension % subspaces);
+    let mut start = 0;
+    (0..subspaces)
+        .map(|i| {
+            let end = start + base + usize::from(i < extra);
+            let range = (start, end);
+            start = end;
+            range
+        })
+        .collect()
+}
+
+fn train_codebooks(
+    config: &QuantizationConfig,
+    vectors: &[ArrayView1<f32>],
+    dimension: usize,
+    subspaces: usize,
+    centroids: usize,
+) -> (Vec<Array2<f32>>, Vec<(usize, usize)>) {
+    let bounds = subspace_bounds(dimension, subspaces);
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let training: Vec<ArrayView1<f32>> = if vectors.len() > TRAINING_SAMPLE {
+        sample(&mut rng, vectors.len(), TRAINING_SAMPLE).into_iter().map(|i| vectors[i]).collect()
+    } else {
+        vectors.to_vec()
+    };
+    let training = normalized(&training, dimension);
+    let centroids = centroids.clamp(1, 256).min(training.nrows().max(1));
+
+    let codebooks = bounds.iter()
+        .map(|&(start, end)| euclidean_kmeans(&training.slice(s![.., start..end]), centroids, config.iterations))
+        .collect();
+    (codebooks, bounds)
+}
+
+// Plain Lloyd's k-means on sub-vectors, seeded with evenly spaced rows
+fn euclidean_kmeans(points: &ArrayView2<f32>, k: usize, iterations: usize) -> Array2<f32> {
+    let n = points.nrows();
+    if n == 0 {
+        return Array2::zeros((1, points.ncols()));
+    }
+    let seeds: Vec<usize> = (0..k).map(|i| i * n / k).collect();
+    let mut centres = points.select(Axis(0), &seeds);
+    for _ in 0..iterations.max(1) {
+        let assignment = assign(points, &centres);
+        let mut sums = Array2::<f32>::zeros(centres.dim());
+        let mut counts = vec![0usize; k];
+        for (point, &c) in points.rows().into_iter().zip(&assignment) {
+            sums.row_mut(c).scaled_add(1.0, &point);
+            counts[c] += 1;
+        }
+        for (c, &count) in counts.iter().enumerate().filter(|(_, &count)| count > 0) {
+            centres.row_mut(c).assign(&(&sums.row(c) / count as f32));
+        }
+    }
+    centres
+}
+
+// Nearest centre of every point: |p - c|² ranks like |c|² - 2 p·c, which is one matrix product
+fn assign(points: &ArrayView2<f32>, centres: &Array2<f32>) -> Vec<usize> {
+    let norms = centres.map_axis(Axis(1), |c| c.dot(&c));
+    let cross = points.dot(&centres.t());
+    cross.rows().into_iter()
+        .map(|row| {
+            row.iter().zip(&norms)
+                .map(|(dot, norm)| norm - 2.0 * dot)
+                .enumerate()
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map_or(0, |(i, _)| i)
+        })
+        .collect()
+}
+
+fn encode_product(vectors: &[ArrayView1<f32>], codebooks: &[Array2<f32>], bounds: &[(usize, usize)]) -> Array2<u8> {
+    let dimension = bounds.last().map_or(0, |b| b.1);
+    let mut codes = Array2::zeros((vectors.len(), bounds.len()));
+    for (offset, chunk) in (0..).step_by(ENCODE_CHUNK).zip(vectors.chunks(ENCODE_CHUNK)) {
+        let rows = normalized(chunk, dimension);
+        for (j, (codebook, &(start, end))) in codebooks.iter().zip(bounds).enumerate() {
+            for (i, centre) in assign(&rows.slice(s![.., start..end]), codebook).into_iter().enumerate() {
+                codes[[offset + i, j]] = centre as u8;
+            }
+        }
+    }
+    codes
+}
+
+impl SimilarityEngine {
+    // Stores a quantized copy of the concepts for the first, approximate pass of
+    // large searches; the top `rescore` candidates are then scored exactly
+    pub fn with_quantization(mut self, config: QuantizationConfig) -> Self {
+        self.set_quantization(Some(config));
+        self
+    }
+
+    pub fn set_quantization(&mut self, config: Option<QuantizationConfig>) {
+        self.quantization = config;
+        *self.quantized.get_mut().unwrap_or_else(|p| p.into_inner()) = None;
+    }
+
+    pub fn quantization_config(&self) -> Option<QuantizationConfig> {
+        self.quantization
+    }
+
+    // Like the index, quantization only stands in for single-vector cosine concepts.
+    // An active HNSW index takes precedence.
+    pub fn uses_quantization(&self) -> bool {
+        self.quantization.is_some_and(|c| self.concepts.len() >= c.exact_below.max(1))
+            && self.matrix_compatible()
+            && !self.uses_index()
+    }
+
+    // Size of the quantized store next to the f32 matrix it replaces in the first pass.
+    // The concept vectors themselves stay for rescoring; see `memory_bytes` for the total.
+    pub fn quantized_memory(&self) -> Result<Option<(usize, usize)>, SimilarityError> {
+        if self.quantization.is_none() {
+            return Ok(None);
+        }
+        self.with_quantized_store(|store| {
+            let full = store.len() * self.dimension().unwrap_or(0) * size_of::<f32>();
+            (store.memory_bytes(), full)
+        }).map(Some)
+    }
+
+    // Fraction of the exact top-`k` concepts that the quantized pass plus rescoring also
+    // returns, averaged over the query rows
+    pub fn quantization_recall(&self, queries: &Array2<f32>, k: usize) -> Result<f32, SimilarityError> {
+        if queries.nrows() == 0 || k == 0 {
+            return Ok(1.0);
+        }
+        let exact = self.score_batch(queries)?;
+        let (mut hits, mut expected) = (0usize, 0usize);
+        for (query, scores) in queries.rows().into_iter().zip(exact.rows()) {
+            let mut ranked: Vec<usize> = (0..scores.len()).collect();
+            ranked.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]));
+            ranked.truncate(k);
+
+            let found: HashSet<usize> = self.quantized_search(&query.to_owned(), k)?
+                .into_iter()
+                .take(k)
+                .map(|(i, _)| i)
+                .collect();
+            hits += ranked.iter().filter(|i| found.contains(i)).count();
+            expected += ranked.len();
+        }
+        Ok(if expected == 0 { 1.0 } else { hits as f32 / expected as f32 })
+    }
+
+    // Approximate candidates re-ranked by their exact similarity, best first
+    pub(crate) fn quantized_search(&self, input_vec: &Array1<f32>, k: usize) -> Result<Vec<(usize, f32)>, SimilarityError> {
+        let norm = input_vec.dot(input_vec).sqrt();
+        let query = if norm > 0.0 { input_vec / norm } else { input_vec.clone() };
+        let rescore = self.quantization.unwrap_or_default().rescore.max(k);
+        let candidates = self.with_quantized_store(|store| store.search(query.view(), rescore))?;
+
+        let mut rescored = Vec::with_capacity(candidates.len());
+        for (i, _) in candidates {
+            rescored.push((i, self.score(&self.concepts[i], input_vec)?));
+        }
+        rescored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        Ok(rescored)
+    }
+
+    // Runs `f` on an up-to-date store, (re)encoding it from the concepts first if needed.
+    // The codes replace the f32 matrix, so any cached copy of it is dropped.
+    fn with_quantized_store<T>(&self, f: impl FnOnce(&QuantizedStore) -> T) -> Result<T, SimilarityError> {
+        {
+            let store = self.quantized.read().unwrap_or_else(|p| p.into_inner());
+            if let Some(store) = store.as_ref().filter(|s| !s.stale && s.len() == self.concepts.len()) {
+                return Ok(f(store));
+            }
+        }
+        let config = self.quantization.unwrap_or_default();
+        let dimension = self.dimension().unwrap_or(0);
+        let mut vectors = Vec::with_capacity(self.concepts.len());
+        for concept in &self.concepts {
+            if concept.vector.len() != dimension {
+                return Err(SimilarityError::mismatch(
+                    dimension, concept.vector.len(), format!("concept '{}'", concept.name)
+                ));
+            }
+            vectors.push(concept.vector.view());
+        }
+        let mut slot = self.quantized.write().unwrap_or_else(|p| p.into_inner());
+        let store = QuantizedStore::build(&config, &vectors, slot.as_ref());
+        let result = f(&store);
+        *slot = Some(store);
+        *self.concept_matrix.write().unwrap_or_else(|p| p.into_inner()) = None;
+        Ok(result)
+    }
+
+    pub(crate) fn quantized_memory_bytes(&self) -> usize {
+        self.quantized.read().unwrap_or_else(|p| p.into_inner()).as_ref().map_or(0, QuantizedStore::memory_bytes)
+    }
+
+    // Concepts changed: the codes must be redone, but trained codebooks can stay
+    pub(crate) fn quantized_stale(&self) {
+        if let Some(store) = self.quantized.write().unwrap_or_else(|p| p.into_inner()).as_mut() {
+            store.stale = true;
+        }
+    }
+}
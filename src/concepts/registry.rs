@@ -0,0 +1,132 @@
+// #FF69B4 Shared Concept Registry
+use super::{ConceptEvent, ConceptId, ConceptVector, SimilarityEngine};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+// Shared, read-only view of an engine's active concepts keyed by id. The engine
+// that created it is the only writer; every change views match on bumps the generation.
+#[derive(Clone, Default)]
+pub struct ConceptRegistry {
+    inner: Arc<RwLock<RegistryState>>,
+}
+
+#[derive(Default)]
+struct RegistryState {
+    concepts: BTreeMap<ConceptId, ConceptVector>,
+    redirects: HashMap<ConceptId, Vec<ConceptId>>, // Merged or split ids → their successors
+    generation: u64,
+}
+
+impl ConceptRegistry {
+    pub(crate) fn from_concepts(concepts: &[ConceptVector]) -> Self {
+        let registry = ConceptRegistry::default();
+        registry.write().concepts = concepts.iter().map(|c| (c.id, c.clone())).collect();
+        registry
+    }
+
+    // Bumped by every change except interaction times; views compare it to decide whether to refresh
+    pub fn generation(&self) -> u64 {
+        self.read().generation
+    }
+
+    pub fn len(&self) -> usize {
+        self.read().concepts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, id: ConceptId) -> Option<ConceptVector> {
+        self.read().concepts.get(&id).cloned()
+    }
+
+    pub fn get_by_name(&self, name: &str) -> Option<ConceptVector> {
+        self.read().concepts.values().find(|c| c.name == name).cloned()
+    }
+
+    // Current ids standing for `ids`, following merges and splits; retired and
+    // removed ids are kept so they reappear if restored
+    pub fn resolve(&self, ids: &[ConceptId]) -> Vec<ConceptId> {
+        let state = self.read();
+        let mut resolved = Vec::new();
+        let mut seen = HashSet::new();
+        let mut pending: Vec<ConceptId> = ids.iter().rev().copied().collect();
+        while let Some(id) = pending.pop() {
+            if !seen.insert(id) {
+                continue;
+            }
+            match state.redirects.get(&id) {
+                Some(successors) if !state.concepts.contains_key(&id) => pending.extend(successors.iter().rev()),
+                _ => resolved.push(id),
+            }
+        }
+        resolved
+    }
+
+    // Active concepts among `ids`, in order
+    pub fn concepts(&self, ids: &[ConceptId]) -> Vec<ConceptVector> {
+        let state = self.read();
+        ids.iter().filter_map(|id| state.concepts.get(id).cloned()).collect()
+    }
+
+    pub(crate) fn apply(&self, event: &ConceptEvent) {
+        let mut state = self.write();
+        match event {
+            ConceptEvent::Added(concept) | ConceptEvent::Updated(concept) | ConceptEvent::Restored(concept) => {
+                state.concepts.insert(concept.id, concept.clone());
+            }
+            ConceptEvent::Removed(id) | ConceptEvent::Retired(id) => {
+                state.concepts.remove(id);
+            }
+            ConceptEvent::Merged { into, absorbed } => {
+                state.concepts.remove(absorbed);
+                state.concepts.insert(into.id, into.clone());
+                state.redirects.insert(*absorbed, vec![into.id]);
+            }
+            ConceptEvent::Split { from, into } => {
+                state.concepts.remove(from);
+                for part in into {
+                    state.concepts.insert(part.id, part.clone());
+                }
+                state.redirects.insert(*from, into.iter().map(|c| c.id).collect());
+            }
+        }
+        state.generation += 1;
+    }
+
+    // Refreshes bookkeeping that views don't match on (interaction times) without
+    // bumping the generation, so modules don't rebuild on every turn
+    pub(crate) fn touch(&self, concept: &ConceptVector) {
+        if let Some(stored) = self.write().concepts.get_mut(&concept.id) {
+            stored.last_interaction_time = concept.last_interaction_time;
+        }
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, RegistryState> {
+        self.inner.read().unwrap_or_else(|p| p.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, RegistryState> {
+        self.inner.write().unwrap_or_else(|p| p.into_inner())
+    }
+}
+
+impl SimilarityEngine {
+    // Starts mirroring this engine's active concepts into a registry other components
+    // can read (on first call) and returns a handle to it
+    pub fn shared_registry(&mut self) -> ConceptRegistry {
+        self.registry.get_or_insert_with(|| ConceptRegistry::from_concepts(&self.concepts)).clone()
+    }
+
+    // Swaps the whole concept set, as views do when their registry moved on; no events are sent.
+    // The dimension follows the incoming concepts, since the owner may have changed embedders.
+    pub(crate) fn replace_concepts(&mut self, concepts: Vec<ConceptVector>) {
+        if let Some(first) = concepts.first() {
+            self.dimension = Some(first.vector.len());
+        }
+        self.concepts = concepts;
+        self.assign_ids();
+        self.invalidate_caches();
+    }
+}
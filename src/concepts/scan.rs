@@ -0,0 +1,69 @@
+// #FF69B4 Exact Similarity Scan
+use super::{SimilarityEngine, SimilarityError};
+use crate::embedding::projection::normalize_rows;
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2};
+#[cfg(feature = "parallel")]
+use ndarray::{s, Axis};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+// Concepts per parallel task; smaller sets are scanned on the calling thread
+#[cfg(feature = "parallel")]
+const CHUNK: usize = 4096;
+
+// Cosine of a normalized query against every row of the normalized concept matrix
+pub(crate) fn scan(matrix: &Array2<f32>, query: ArrayView1<f32>) -> Array1<f32> {
+    #[cfg(feature = "parallel")]
+    if matrix.nrows() > CHUNK {
+        let parts: Vec<Array1<f32>> = matrix.axis_chunks_iter(Axis(0), CHUNK)
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .map(|chunk| chunk.dot(&query))
+            .collect();
+        let mut scores = Array1::zeros(matrix.nrows());
+        for (start, part) in (0..).step_by(CHUNK).zip(parts) {
+            scores.slice_mut(s![start..start + part.len()]).assign(&part);
+        }
+        return scores;
+    }
+    matrix.dot(&query)
+}
+
+// Cosine of every normalized input row against every concept, shape (inputs, concepts)
+pub(crate) fn scan_batch(matrix: &Array2<f32>, inputs: ArrayView2<f32>) -> Array2<f32> {
+    #[cfg(feature = "parallel")]
+    if matrix.nrows() > CHUNK {
+        let parts: Vec<Array2<f32>> = matrix.axis_chunks_iter(Axis(0), CHUNK)
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .map(|chunk| inputs.dot(&chunk.t()))
+            .collect();
+        let mut scores = Array2::zeros((inputs.nrows(), matrix.nrows()));
+        for (start, part) in (0..).step_by(CHUNK).zip(parts) {
+            scores.slice_mut(s![.., start..start + part.ncols()]).assign(&part);
+        }
+        return scores;
+    }
+    inputs.dot(&matrix.t())
+}
+
+impl SimilarityEngine {
+    // Exact cosine against every concept through the cached matrix: concept norms are
+    // folded in once when the matrix is built and the query is normalized once here.
+    // Only meaningful when `matrix_compatible` holds.
+    pub(crate) fn scan_scores(&self, input_vec: &Array1<f32>) -> Result<Array1<f32>, SimilarityError> {
+        let matrix = self.concept_matrix()?;
+        let norm = input_vec.dot(input_vec).sqrt();
+        if norm == 0.0 {
+            return Ok(Array1::zeros(matrix.nrows())); // As `cosine_similarity` does
+        }
+        Ok(scan(&matrix, (input_vec / norm).view()))
+    }
+
+    pub(crate) fn scan_scores_batch(&self, inputs: &Array2<f32>) -> Result<Array2<f32>, SimilarityError> {
+        let matrix = self.concept_matrix()?;
+        let mut normalized = inputs.to_owned();
+        normalize_rows(&mut normalized);
+        Ok(scan_batch(&matrix, normalized.view()))
+    }
+}
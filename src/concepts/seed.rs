@@ -0,0 +1,286 @@
+// #FF69B4 Seed-Text Prototypes
+use super::discovery::kmeans;
+use super::prototypes::centroid;
+use super::{ConceptEvent, ConceptId, ConceptVector, SimilarityEngine, SimilarityError, SimilarityMetric};
+use crate::embedding::projection::normalize_rows;
+use crate::embedding::Embedder;
+use ndarray::{Array1, Array2, Axis};
+use serde::{Deserialize, Serialize};
+
+// Gap kept below the weakest positive example when no negatives are given
+const POSITIVE_MARGIN: f32 = 0.05;
+const SEED_CLUSTER_ITERATIONS: usize = 25;
+
+// How example phrases are reduced to a single concept vector
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PrototypeStrategy {
+    #[default]
+    Centroid, // Normalized mean of the example embeddings
+    Medoid,   // The example closest to all the others
+}
+
+// Result of embedding a concept's seed texts
+#[derive(Debug, Clone)]
+pub struct SeedPrototype {
+    pub vector: Array1<f32>,
+    pub covariance: Array1<f32>,       // Per-dimension spread of the examples, for Mahalanobis
+    pub suggested_threshold: f32,
+    pub weakest_positive: f32,         // Lowest similarity of a positive example to the prototype
+    pub strongest_negative: Option<f32>, // Highest similarity of a negative example
+}
+
+// Embeds positive/negative examples and derives a prototype plus a threshold that
+// separates them, measured in the metric the concept will be scored with
+pub fn derive_prototype(
+    embedder: &dyn Embedder,
+    positives: &[String],
+    negatives: &[String],
+    strategy: PrototypeStrategy,
+    metric: SimilarityMetric,
+) -> anyhow::Result<SeedPrototype> {
+    if positives.is_empty() {
+        anyhow::bail!("at least one positive seed text is required");
+    }
+
+    let texts: Vec<&str> = positives.iter().map(String::as_str).collect();
+    let mut examples = embedder.embed_batch(&texts)?;
+    normalize_rows(&mut examples);
+
+    let mut vector = match strategy {
+        PrototypeStrategy::Centroid => examples.mean_axis(Axis(0)).expect("non-empty seed batch"),
+        PrototypeStrategy::Medoid => medoid(&examples),
+    };
+    let norm = vector.dot(&vector).sqrt();
+    if norm > 0.0 {
+        vector.mapv_inplace(|x| x / norm);
+    }
+
+    let covariance = diagonal_covariance(&examples, &vector);
+    let similarity = |row: ndarray::ArrayView1<f32>| metric.similarity(&vector, &row.to_owned(), Some(&covariance));
+
+    let weakest_positive = examples.rows().into_iter()
+        .map(similarity)
+        .fold(f32::INFINITY, f32::min);
+
+    let strongest_negative = if negatives.is_empty() {
+        None
+    } else {
+        let texts: Vec<&str> = negatives.iter().map(String::as_str).collect();
+        let rejected = embedder.embed_batch(&texts)?;
+        Some(rejected.rows().into_iter()
+            .map(similarity)
+            .fold(f32::NEG_INFINITY, f32::max))
+    };
+
+    // Midway between the classes when negatives exist, otherwise just under the positives
+    let suggested_threshold = match strongest_negative {
+        Some(negative) => (weakest_positive + negative) / 2.0,
+        None => weakest_positive - POSITIVE_MARGIN,
+    };
+    let suggested_threshold = match metric {
+        SimilarityMetric::Cosine => suggested_threshold.clamp(-1.0, 1.0),
+        _ => suggested_threshold,
+    };
+
+    Ok(SeedPrototype { vector, covariance, suggested_threshold, weakest_positive, strongest_negative })
+}
+
+// Per-dimension variance around the prototype, shrunk halfway towards the isotropic
+// variance of a unit vector so one or two examples don't give zero-width dimensions
+fn diagonal_covariance(examples: &Array2<f32>, prototype: &Array1<f32>) -> Array1<f32> {
+    let isotropic = 1.0 / prototype.len().max(1) as f32;
+    let mut variance = Array1::zeros(prototype.len());
+    for row in examples.rows() {
+        variance += &(&row - prototype).mapv(|d| d * d);
+    }
+    variance.mapv(|v: f32| 0.5 * v / examples.nrows() as f32 + 0.5 * isotropic)
+}
+
+// Re-embeds the seeds and groups them into at most `count` clusters, one prototype each.
+// Multi-prototype concepts use it to keep their exemplars in the embedder's space.
+fn seed_prototypes(embedder: &dyn Embedder, seeds: &[String], count: usize, seed: u64) -> anyhow::Result<Vec<Array1<f32>>> {
+    let texts: Vec<&str> = seeds.iter().map(String::as_str).collect();
+    let mut examples = embedder.embed_batch(&texts)?;
+    normalize_rows(&mut examples);
+    let clusters = kmeans(&examples, count, SEED_CLUSTER_ITERATIONS, seed);
+    if clusters.len() < 2 {
+        return Ok(Vec::new()); // One group is just the single prototype
+    }
+    Ok(clusters.iter()
+        .map(|members| centroid(&members.iter().map(|&i| examples.row(i).to_owned()).collect::<Vec<_>>()))
+        .collect())
+}
+
+fn medoid(examples: &Array2<f32>) -> Array1<f32> {
+    let similarities = examples.dot(&examples.t());
+    let best = similarities.rows().into_iter()
+        .map(|row| row.sum())
+        .enumerate()
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(i, _)| i)
+        .unwrap_or(0);
+    examples.row(best).to_owned()
+}
+
+impl ConceptVector {
+    // Builds a concept from example phrases using the given embedder
+    pub fn from_seeds(
+        name: &str,
+        embedder: &dyn Embedder,
+        positives: Vec<String>,
+        negatives: Vec<String>,
+        strategy: PrototypeStrategy,
+    ) -> anyhow::Result<Self> {
+        Self::from_seeds_with_metric(name, embedder, positives, negatives, strategy, None)
+    }
+
+    // Like `from_seeds`, with the threshold placed in the units of `metric` (cosine when `None`)
+    pub fn from_seeds_with_metric(
+        name: &str,
+        embedder: &dyn Embedder,
+        positives: Vec<String>,
+        negatives: Vec<String>,
+        strategy: PrototypeStrategy,
+        metric: Option<SimilarityMetric>,
+    ) -> anyhow::Result<Self> {
+        let prototype = derive_prototype(embedder, &positives, &negatives, strategy, metric.unwrap_or_default())?;
+        Ok(ConceptVector {
+            name: name.to_string(),
+            vector: prototype.vector,
+            covariance: Some(prototype.covariance),
+            threshold: prototype.suggested_threshold,
+            seed_texts: positives,
+            negative_texts: negatives,
+            prototype: strategy,
+            auto_threshold: true,
+            metric,
+            ..ConceptVector::default()
+        })
+    }
+
+    pub fn is_seeded(&self) -> bool {
+        !self.seed_texts.is_empty()
+    }
+}
+
+impl SimilarityEngine {
+    // Embeds the examples with the active embedder and adds the resulting concept,
+    // with its threshold in the engine's metric
+    pub fn add_seeded_concept(
+        &mut self,
+        name: &str,
+        positives: Vec<String>,
+        negatives: Vec<String>,
+    ) -> Result<ConceptId, SimilarityError> {
+        let embedder = self.embedder().ok_or(SimilarityError::NoEmbedder)?;
+        let mut concept = ConceptVector::from_seeds_with_metric(
+            name, embedder, positives, negatives, PrototypeStrategy::default(), Some(self.metric)
+        )
+        .map_err(|e| SimilarityError::embedding(embedder.model_id(), e))?;
+        concept.metric = None; // Follow the engine default rather than pinning it
+        self.add_concept(concept)
+    }
+
+    // Recomputes every seeded concept and every negative phrase with the active embedder,
+    // retired ones included so they can be restored into the new space.
+    // Thresholds are only replaced where they were suggested rather than hand-set.
+    pub fn rederive_seeded_concepts(&mut self) -> Result<usize, SimilarityError> {
+        let Some(embedder) = self.embedder() else {
+            return Ok(0);
+        };
+        let failed = |e| SimilarityError::embedding(embedder.model_id(), e);
+        // (retired, position) pairs, so both lists are updated in one pass
+        let all = || {
+            self.concepts.iter().enumerate().map(|(i, c)| ((false, i), c))
+                .chain(self.retired.iter().enumerate().map(|(i, c)| ((true, i), c)))
+        };
+
+        let mut derived = Vec::new();
+        for (key, concept) in all().filter(|(_, c)| c.is_seeded()) {
+            let metric = self.metric_for(concept);
+            let prototype = derive_prototype(embedder, &concept.seed_texts, &concept.negative_texts, concept.prototype, metric)
+                .map_err(failed)?;
+            let prototypes = if concept.is_multi_prototype() {
+                seed_prototypes(embedder, &concept.seed_texts, concept.prototypes.len(), concept.id.0).map_err(failed)?
+            } else {
+                Vec::new()
+            };
+            derived.push((key, prototype, prototypes));
+        }
+        let dimension = if derived.iter().all(|((retired, _), _, _)| *retired) {
+            self.dimension()
+        } else {
+            Some(embedder.dimension())
+        };
+
+        let mut negatives = Vec::new();
+        for (key, concept) in all() {
+            let Some(exclusion) = &concept.exclusion else {
+                continue;
+            };
+            // Hand-authored vectors can't follow the embedder; phrases can.
+            // Retired concepts are checked again when they are restored.
+            let (retired, _) = key;
+            if let Some(i) = exclusion.negatives.iter().position(|n| !retired && Some(n.len()) != dimension) {
+                return Err(SimilarityError::InvalidExclusion {
+                    concept: concept.name.clone(),
+                    reason: format!("negative #{i} does not fit embedder '{}'; describe it as text instead", embedder.model_id()),
+                });
+            }
+            if !exclusion.texts.is_empty() {
+                let texts: Vec<&str> = exclusion.texts.iter().map(String::as_str).collect();
+                let embedded = embedder.embed_batch(&texts).map_err(failed)?;
+                negatives.push((key, embedded.rows().into_iter().map(|r| r.to_owned()).collect::<Vec<_>>()));
+            }
+        }
+
+        // Build the new forms first and check the active ones against the new size,
+        // so nothing is applied unless every concept still fits
+        let count = derived.len();
+        let active = derived.iter().any(|((retired, _), _, _)| !retired);
+        let mut updated = std::collections::BTreeMap::new();
+        for ((retired, index), prototype, prototypes) in derived {
+            let mut concept = if retired { self.retired[index].clone() } else { self.concepts[index].clone() };
+            concept.vector = prototype.vector;
+            concept.prototypes = prototypes;
+            concept.covariance = Some(prototype.covariance);
+            concept.learned = None; // Learned offsets don't carry over to a new embedding space
+            if concept.auto_threshold {
+                concept.threshold = prototype.suggested_threshold;
+            }
+            updated.insert((retired, index), concept);
+        }
+        for ((retired, index), embedded) in negatives {
+            let concept = updated.entry((retired, index))
+                .or_insert_with(|| if retired { self.retired[index].clone() } else { self.concepts[index].clone() });
+            if let Some(exclusion) = concept.exclusion.as_mut() {
+                exclusion.text_negatives = embedded;
+            }
+        }
+        for ((retired, _), concept) in &updated {
+            if !retired {
+                Self::check_concept_shape(concept, dimension)?;
+            }
+        }
+
+        let mut changed = std::collections::BTreeSet::new();
+        for ((retired, index), concept) in updated {
+            if retired {
+                self.retired[index] = concept;
+            } else {
+                self.concepts[index] = concept;
+            }
+            changed.insert((retired, index));
+        }
+        // Retired concepts aren't shared, so only active ones are announced
+        for (_, index) in changed.into_iter().filter(|(retired, _)| !retired) {
+            self.notify(ConceptEvent::Updated(self.concepts[index].clone()));
+        }
+        if active {
+            self.dimension = dimension;
+            self.invalidate_caches();
+        }
+        Ok(count)
+    }
+}
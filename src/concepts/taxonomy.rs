@@ -0,0 +1,144 @@
+// #FF69B4 Concept Taxonomy
+use super::{ConceptVector, SimilarityEngine, SimilarityError};
+use ndarray::Array1;
+use std::collections::{BTreeMap, HashSet};
+
+// Joins concept names into paths such as "Verification/Code review"
+pub const PATH_SEPARATOR: char = '/';
+
+// Deepest concept reached by refining from a root through its children
+#[derive(Debug, Clone)]
+pub struct HierarchicalMatch {
+    pub concept: ConceptVector,
+    pub path: String,
+    pub similarity: f32,
+    pub depth: usize, // 0 for a root
+}
+
+impl HierarchicalMatch {
+    // First path segment, which is what the action system dispatches on
+    pub fn root(&self) -> &str {
+        self.path.split(PATH_SEPARATOR).next().unwrap_or(&self.path)
+    }
+}
+
+impl SimilarityEngine {
+    pub fn has_taxonomy(&self) -> bool {
+        self.concepts.iter().any(|c| c.parent.is_some())
+    }
+
+    pub fn concept(&self, name: &str) -> Option<&ConceptVector> {
+        self.concepts.iter().find(|c| c.name == name)
+    }
+
+    pub fn parent_of(&self, name: &str) -> Option<&ConceptVector> {
+        self.concept(name)?.parent.as_deref().and_then(|parent| self.concept(parent))
+    }
+
+    // Direct children of `parent`, or the roots when `parent` is `None`.
+    // Concepts whose parent is missing count as roots so they stay reachable.
+    pub fn children_of(&self, parent: Option<&str>) -> Vec<&ConceptVector> {
+        self.concepts.iter().filter(|c| self.is_child_of(c, parent)).collect()
+    }
+
+    fn is_child_of(&self, concept: &ConceptVector, parent: Option<&str>) -> bool {
+        match (parent, concept.parent.as_deref()) {
+            (Some(wanted), Some(actual)) => wanted == actual,
+            (None, Some(actual)) => self.concept(actual).is_none(),
+            (None, None) => true,
+            (Some(_), None) => false,
+        }
+    }
+
+    // Root-first names joined with `PATH_SEPARATOR`
+    pub fn path_of(&self, name: &str) -> Option<String> {
+        let mut segments = vec![self.concept(name)?.name.as_str()];
+        let mut current = name;
+        while let Some(parent) = self.parent_of(current) {
+            if segments.len() > self.concepts.len() {
+                break; // Cycle; `validate_taxonomy` reports it
+            }
+            segments.push(parent.name.as_str());
+            current = &parent.name;
+        }
+        segments.reverse();
+        Some(segments.join(&PATH_SEPARATOR.to_string()))
+    }
+
+    // Metadata merged from the root down; a child's own keys override its ancestors'
+    pub fn inherited_metadata(&self, name: &str) -> BTreeMap<String, String> {
+        let mut chain = Vec::new();
+        let mut current = self.concept(name);
+        while let Some(concept) = current {
+            if chain.len() > self.concepts.len() {
+                break;
+            }
+            chain.push(concept);
+            current = self.parent_of(&concept.name);
+        }
+        chain.into_iter().rev().flat_map(|c| c.metadata.clone()).collect()
+    }
+
+    // Every parent must exist, and following parents must never loop
+    pub fn validate_taxonomy(&self) -> Result<(), SimilarityError> {
+        for concept in &self.concepts {
+            let invalid = |reason: String| SimilarityError::InvalidTaxonomy { concept: concept.name.clone(), reason };
+            if let Some(parent) = &concept.parent {
+                if self.concept(parent).is_none() {
+                    return Err(invalid(format!("parent '{parent}' does not exist")));
+                }
+            }
+            let mut seen = HashSet::from([concept.name.as_str()]);
+            let mut current = concept.name.as_str();
+            while let Some(parent) = self.parent_of(current) {
+                if !seen.insert(parent.name.as_str()) {
+                    return Err(invalid("parent links form a cycle".to_string()));
+                }
+                current = &parent.name;
+            }
+        }
+        Ok(())
+    }
+
+    // Matches among the roots first, then refines into the winner's children for as
+    // long as one of them also passes its threshold
+    pub fn try_find_hierarchical_match(&self, input_vec: &Array1<f32>) -> Result<Option<HierarchicalMatch>, SimilarityError> {
+        self.check_dimension(input_vec.len(), "query")?;
+        self.hierarchical_match(input_vec, &self.draw_thresholds())
+    }
+
+    // The walk itself, with thresholds already drawn for this query
+    pub(crate) fn hierarchical_match(&self, input_vec: &Array1<f32>, thresholds: &[f32]) -> Result<Option<HierarchicalMatch>, SimilarityError> {
+        let mixed = self.mixed_metrics();
+        let mut found: Option<HierarchicalMatch> = None;
+        let mut parent: Option<&str> = None;
+        let mut visited = HashSet::new();
+        loop {
+            let mut best: Option<(&ConceptVector, f32, f32)> = None;
+            for (i, concept) in self.concepts.iter().enumerate().filter(|(_, c)| self.is_child_of(c, parent)) {
+                let Some(similarity) = self.screened(concept, input_vec, self.score(concept, input_vec)?)? else {
+                    continue;
+                };
+                let threshold = thresholds[i];
+                let rank = Self::rank_score(similarity, threshold, mixed);
+                if similarity > threshold && best.is_none_or(|(_, _, r)| rank >= r) {
+                    best = Some((concept, similarity, rank));
+                }
+            }
+            let Some((concept, similarity, _)) = best else {
+                return Ok(found);
+            };
+            if !visited.insert(concept.name.as_str()) {
+                return Ok(found);
+            }
+
+            let path = match &found {
+                Some(parent) => format!("{}{PATH_SEPARATOR}{}", parent.path, concept.name),
+                None => concept.name.clone(),
+            };
+            let depth = found.as_ref().map_or(0, |parent| parent.depth + 1);
+            found = Some(HierarchicalMatch { concept: concept.clone(), path, similarity, depth });
+            parent = Some(&concept.name);
+        }
+    }
+}
@@ -0,0 +1,279 @@
+// #9400D3 Embedding Cache (LRU + on-disk store)
+use super::Embedder;
+use anyhow::{anyhow, Context, Result};
+use lru::LruCache;
+use ndarray::{Array1, Array2};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+// Hit/miss counters for a cache instance
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub disk_hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub memory_entries: usize,
+    pub disk_entries: usize,
+}
+
+impl CacheStats {
+    // Fraction of lookups served without calling the wrapped embedder
+    pub fn hit_rate(&self) -> f32 {
+        let total = self.hits + self.disk_hits + self.misses;
+        if total == 0 {
+            return 0.0;
+        }
+        (self.hits + self.disk_hits) as f32 / total as f32
+    }
+}
+
+// One persisted embedding per line
+#[derive(Serialize, Deserialize)]
+struct DiskRecord {
+    model: String,
+    text: String,
+    vector: Vec<f32>,
+}
+
+// Append-only JSON-lines file indexed by byte offset, so only the index stays in memory
+struct DiskStore {
+    path: PathBuf,
+    file: File,
+    offsets: HashMap<String, u64>,
+    model_id: String,
+    dimension: usize,
+}
+
+impl DiskStore {
+    fn open(path: &Path, model_id: &str, dimension: usize) -> Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)
+            .with_context(|| format!("failed to open embedding cache {}", path.display()))?;
+
+        let mut offsets = HashMap::new();
+        let mut reader = BufReader::new(&file);
+        let mut offset = 0u64;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let read = reader.read_line(&mut line)?;
+            if read == 0 {
+                break;
+            }
+            // Skip torn or foreign lines rather than refusing to start
+            if let Ok(record) = serde_json::from_str::<DiskRecord>(&line) {
+                if record.model == model_id && record.vector.len() == dimension {
+                    offsets.insert(record.text, offset);
+                }
+            }
+            offset += read as u64;
+        }
+
+        Ok(DiskStore { path: path.to_path_buf(), file, offsets, model_id: model_id.to_string(), dimension })
+    }
+
+    fn get(&mut self, text: &str) -> Result<Option<Array1<f32>>> {
+        let Some(&offset) = self.offsets.get(text) else {
+            return Ok(None);
+        };
+        self.file.seek(SeekFrom::Start(offset))?;
+        let mut line = String::new();
+        BufReader::new(&self.file).read_line(&mut line)?;
+        // A corrupt entry, or one that doesn't fit the model (the file changed underneath us),
+        // is a miss, and forgetting it lets the fresh embedding be appended
+        match serde_json::from_str::<DiskRecord>(&line) {
+            Ok(record) if record.model == self.model_id && record.vector.len() == self.dimension => {
+                Ok(Some(Array1::from_vec(record.vector)))
+            }
+            _ => {
+                self.offsets.remove(text);
+                Ok(None)
+            }
+        }
+    }
+
+    fn insert(&mut self, text: &str, vector: &Array1<f32>) -> Result<()> {
+        if self.offsets.contains_key(text) {
+            return Ok(());
+        }
+        let offset = self.file.seek(SeekFrom::End(0))?;
+        let record = DiskRecord {
+            model: self.model_id.clone(),
+            text: text.to_string(),
+            vector: vector.to_vec(),
+        };
+        let mut line = serde_json::to_string(&record)?;
+        line.push('\n');
+        self.file.write_all(line.as_bytes())
+            .with_context(|| format!("failed to write embedding cache {}", self.path.display()))?;
+        self.offsets.insert(text.to_string(), offset);
+        Ok(())
+    }
+}
+
+struct CacheState {
+    memory: LruCache<String, Array1<f32>>,
+    disk: Option<DiskStore>,
+    stats: CacheStats,
+}
+
+// Caching layer usable in front of any embedding backend
+pub struct CachedEmbedder<E: Embedder> {
+    inner: E,
+    state: Mutex<CacheState>,
+}
+
+impl<E: Embedder> CachedEmbedder<E> {
+    // In-memory LRU holding at most `capacity` embeddings
+    pub fn new(inner: E, capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        CachedEmbedder {
+            inner,
+            state: Mutex::new(CacheState {
+                memory: LruCache::new(capacity),
+                disk: None,
+                stats: CacheStats::default(),
+            }),
+        }
+    }
+
+    // Adds a persistent store; entries written by other models in the same file are ignored
+    pub fn with_disk_store(mut self, path: impl AsRef<Path>) -> Result<Self> {
+        self.set_disk_store(path)?;
+        Ok(self)
+    }
+
+    pub fn set_disk_store(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let disk = DiskStore::open(path.as_ref(), self.inner.model_id(), self.inner.dimension())?;
+        self.lock()?.disk = Some(disk);
+        Ok(())
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut stats = state.stats;
+        stats.memory_entries = state.memory.len();
+        stats.disk_entries = state.disk.as_ref().map_or(0, |d| d.offsets.len());
+        stats
+    }
+
+    pub fn inner(&self) -> &E {
+        &self.inner
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, CacheState>> {
+        self.state.lock().map_err(|_| anyhow!("embedding cache lock poisoned"))
+    }
+
+    // Looks in memory first, then on disk (promoting disk hits into memory)
+    fn lookup(&self, state: &mut CacheState, key: &str) -> Result<Option<Array1<f32>>> {
+        if let Some(vector) = state.memory.get(key) {
+            state.stats.hits += 1;
+            return Ok(Some(vector.clone()));
+        }
+        if let Some(vector) = state.disk.as_mut().map(|d| d.get(key)).transpose()?.flatten() {
+            state.stats.disk_hits += 1;
+            Self::remember(state, key, vector.clone());
+            return Ok(Some(vector));
+        }
+        state.stats.misses += 1;
+        Ok(None)
+    }
+
+    fn store(&self, state: &mut CacheState, key: &str, vector: &Array1<f32>) -> Result<()> {
+        Self::remember(state, key, vector.clone());
+        if let Some(disk) = state.disk.as_mut() {
+            disk.insert(key, vector)?;
+        }
+        Ok(())
+    }
+
+    fn remember(state: &mut CacheState, key: &str, vector: Array1<f32>) {
+        let existed = state.memory.contains(key);
+        if state.memory.push(key.to_string(), vector).is_some() && !existed {
+            state.stats.evictions += 1;
+        }
+    }
+}
+
+impl<E: Embedder> Embedder for CachedEmbedder<E> {
+    fn embed(&self, text: &str) -> Result<Array1<f32>> {
+        let key = normalize_text(text);
+        let mut state = self.lock()?;
+        if let Some(vector) = self.lookup(&mut state, &key)? {
+            return Ok(vector);
+        }
+
+        // Embed outside the lock so slow backends don't serialize cache hits.
+        // The key only finds the entry; the backend sees the caller's text.
+        drop(state);
+        let vector = self.inner.embed(text)?;
+        let mut state = self.lock()?;
+        self.store(&mut state, &key, &vector)?;
+        Ok(vector)
+    }
+
+    fn embed_batch(&self, texts: &[&str]) -> Result<Array2<f32>> {
+        let keys: Vec<String> = texts.iter().map(|text| normalize_text(text)).collect();
+        let mut batch = Array2::zeros((texts.len(), self.dimension()));
+
+        // Rows whose key missed, grouped so repeats within the batch are embedded once
+        let mut missing: Vec<Vec<usize>> = Vec::new();
+        {
+            let mut state = self.lock()?;
+            let mut pending: HashMap<&str, usize> = HashMap::new();
+            for (i, key) in keys.iter().enumerate() {
+                if let Some(&group) = pending.get(key.as_str()) {
+                    state.stats.hits += 1; // Served by the first occurrence's embedding
+                    missing[group].push(i);
+                    continue;
+                }
+                match self.lookup(&mut state, key)? {
+                    Some(vector) => batch.row_mut(i).assign(&vector),
+                    None => {
+                        pending.insert(key, missing.len());
+                        missing.push(vec![i]);
+                    }
+                }
+            }
+        }
+
+        // Only the misses go to the wrapped embedder, in a single batch
+        if !missing.is_empty() {
+            let misses: Vec<&str> = missing.iter().map(|rows| texts[rows[0]]).collect();
+            let computed = self.inner.embed_batch(&misses)?;
+            let mut state = self.lock()?;
+            for (row, rows) in computed.rows().into_iter().zip(&missing) {
+                let vector = row.to_owned();
+                self.store(&mut state, &keys[rows[0]], &vector)?;
+                for &i in rows {
+                    batch.row_mut(i).assign(&vector);
+                }
+            }
+        }
+        Ok(batch)
+    }
+
+    fn dimension(&self) -> usize {
+        self.inner.dimension()
+    }
+
+    fn model_id(&self) -> &str {
+        self.inner.model_id()
+    }
+}
+
+// Cache key text: trimmed with internal whitespace collapsed, so texts that differ
+// only in spacing share one entry
+pub fn normalize_text(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
@@ -0,0 +1,142 @@
+// #9400D3 Hashing N-gram Embedder
+use super::Embedder;
+use anyhow::{bail, Result};
+use ndarray::Array1;
+
+// Feature-hashing settings; the defaults work well for short user inputs
+#[derive(Debug, Clone)]
+pub struct HashingConfig {
+    pub dimension: usize,
+    pub char_ngram_min: usize,
+    pub char_ngram_max: usize,
+    pub char_weight: f32,
+    pub unigram_weight: f32,
+    pub bigram_weight: f32,
+    pub seed: u64,
+}
+
+impl Default for HashingConfig {
+    fn default() -> Self {
+        HashingConfig {
+            dimension: 256,
+            char_ngram_min: 3,
+            char_ngram_max: 5,
+            char_weight: 0.5,
+            unigram_weight: 1.0,
+            bigram_weight: 0.75,
+            seed: 0,
+        }
+    }
+}
+
+// Dependency-free embedder that hashes character n-grams and word uni/bigrams
+pub struct HashingEmbedder {
+    config: HashingConfig,
+    model_id: String,
+}
+
+impl HashingEmbedder {
+    pub fn new(dimension: usize) -> Result<Self> {
+        Self::with_config(HashingConfig { dimension, ..HashingConfig::default() })
+    }
+
+    pub fn with_config(config: HashingConfig) -> Result<Self> {
+        if config.dimension == 0 {
+            bail!("hashing embedder dimension must be greater than zero");
+        }
+        if config.char_ngram_min == 0 || config.char_ngram_min > config.char_ngram_max {
+            bail!(
+                "invalid character n-gram range {}..={}",
+                config.char_ngram_min, config.char_ngram_max
+            );
+        }
+        // Every setting that changes the output is part of the id, since caches key on it
+        let model_id = format!(
+            "hashing-ngram-d{}-c{}-{}-w{}-{}-{}-s{}",
+            config.dimension, config.char_ngram_min, config.char_ngram_max,
+            config.char_weight, config.unigram_weight, config.bigram_weight, config.seed
+        );
+        Ok(HashingEmbedder { config, model_id })
+    }
+
+    pub fn config(&self) -> &HashingConfig {
+        &self.config
+    }
+
+    // Adds a signed feature into its hashed bucket
+    fn add_feature(&self, vector: &mut Array1<f32>, kind: u8, feature: &str, weight: f32) {
+        let hash = finalize(fnv1a(self.config.seed ^ u64::from(kind), feature.as_bytes()));
+        let bucket = (hash % self.config.dimension as u64) as usize;
+        // The top bit picks the sign so collisions tend to cancel out
+        let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+        vector[bucket] += sign * weight;
+    }
+}
+
+impl Embedder for HashingEmbedder {
+    fn embed(&self, text: &str) -> Result<Array1<f32>> {
+        let mut vector = Array1::zeros(self.config.dimension);
+        let words = tokenize(text);
+
+        for word in &words {
+            self.add_feature(&mut vector, b'w', word, self.config.unigram_weight);
+
+            // Character n-grams over the word with boundary markers
+            let chars: Vec<char> = format!("<{word}>").chars().collect();
+            for n in self.config.char_ngram_min..=self.config.char_ngram_max {
+                for gram in chars.windows(n) {
+                    let gram: String = gram.iter().collect();
+                    self.add_feature(&mut vector, b'c', &gram, self.config.char_weight);
+                }
+            }
+        }
+
+        for pair in words.windows(2) {
+            let bigram = format!("{} {}", pair[0], pair[1]);
+            self.add_feature(&mut vector, b'b', &bigram, self.config.bigram_weight);
+        }
+
+        let norm = vector.dot(&vector).sqrt();
+        if norm > 0.0 {
+            vector.mapv_inplace(|x| x / norm);
+        }
+        Ok(vector)
+    }
+
+    fn dimension(&self) -> usize {
+        self.config.dimension
+    }
+
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+}
+
+// Lowercased alphanumeric words
+pub(crate) fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect()
+}
+
+// FNV-1a is stable across platforms and Rust versions, unlike `DefaultHasher`
+pub(crate) fn fnv1a(seed: u64, bytes: &[u8]) -> u64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut hash = OFFSET ^ seed.wrapping_mul(PRIME);
+    for &byte in bytes {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(PRIME);
+    }
+    hash
+}
+
+// Spreads FNV output across all bits before taking the bucket and sign
+fn finalize(mut hash: u64) -> u64 {
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    hash ^= hash >> 33;
+    hash
+}
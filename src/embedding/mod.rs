@@ -1,10 +1,93 @@
 // #9400D3 Embedding Generator
-use ndarray::Array1;
-use anyhow::Result;
+use ndarray::{Array1, Array2};
+use anyhow::{anyhow, Result};
+use std::sync::Arc;
 
+pub mod cache;
+pub mod hashing;
+pub mod projection;
+pub mod tfidf;
+#[cfg(feature = "onnx")]
+pub mod onnx;
+
+pub use cache::{CacheStats, CachedEmbedder};
+pub use hashing::{HashingConfig, HashingEmbedder};
+pub use tfidf::{Reduction, TfidfConfig, TfidfEmbedder, TfidfModel};
+
+#[cfg(feature = "onnx")]
+pub use onnx::{OnnxConfig, OnnxEmbedder};
+
+// Common interface for every embedding backend
+pub trait Embedder: Send + Sync {
+    // Embeds a single piece of text into a fixed-size vector
+    fn embed(&self, text: &str) -> Result<Array1<f32>>;
+
+    // Embeds several texts at once, one row per input
+    fn embed_batch(&self, texts: &[&str]) -> Result<Array2<f32>> {
+        let mut batch = Array2::zeros((texts.len(), self.dimension()));
+        for (mut row, text) in batch.rows_mut().into_iter().zip(texts) {
+            let vector = self.embed(text)?;
+            if vector.len() != row.len() {
+                return Err(anyhow!(
+                    "embedder '{}' returned {} values, expected {}",
+                    self.model_id(), vector.len(), row.len()
+                ));
+            }
+            row.assign(&vector);
+        }
+        Ok(batch)
+    }
+
+    // Length of the vectors produced by `embed`
+    fn dimension(&self) -> usize;
+
+    // Stable identifier of the model behind this embedder
+    fn model_id(&self) -> &str;
+}
+
+impl<E: Embedder + ?Sized> Embedder for Box<E> {
+    fn embed(&self, text: &str) -> Result<Array1<f32>> {
+        (**self).embed(text)
+    }
+
+    fn embed_batch(&self, texts: &[&str]) -> Result<Array2<f32>> {
+        (**self).embed_batch(texts)
+    }
+
+    fn dimension(&self) -> usize {
+        (**self).dimension()
+    }
+
+    fn model_id(&self) -> &str {
+        (**self).model_id()
+    }
+}
+
+impl<E: Embedder + ?Sized> Embedder for Arc<E> {
+    fn embed(&self, text: &str) -> Result<Array1<f32>> {
+        (**self).embed(text)
+    }
+
+    fn embed_batch(&self, texts: &[&str]) -> Result<Array2<f32>> {
+        (**self).embed_batch(texts)
+    }
+
+    fn dimension(&self) -> usize {
+        (**self).dimension()
+    }
+
+    fn model_id(&self) -> &str {
+        (**self).model_id()
+    }
+}
+
+// Length-based mock embedder used by the MVP
 pub struct EmbeddingGenerator;
 
 impl EmbeddingGenerator {
+    pub const MODEL_ID: &'static str = "mock-length-v0";
+    pub const DIMENSION: usize = 3;
+
     pub fn new() -> Result<Self> {
         Ok(Self)
     }
@@ -22,3 +105,17 @@ impl EmbeddingGenerator {
         Ok(Array1::from_vec(vec.into_iter().map(|x| x/norm).collect()))
     }
 }
+
+impl Embedder for EmbeddingGenerator {
+    fn embed(&self, text: &str) -> Result<Array1<f32>> {
+        EmbeddingGenerator::embed(self, text)
+    }
+
+    fn dimension(&self) -> usize {
+        Self::DIMENSION
+    }
+
+    fn model_id(&self) -> &str {
+        Self::MODEL_ID
+    }
+}
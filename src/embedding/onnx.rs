@@ -0,0 +1,175 @@
+// #9400D3 ONNX Sentence Embeddings (all-MiniLM-L6-v2)
+use super::Embedder;
+use anyhow::{anyhow, bail, Context, Result};
+use ndarray::{Array1, Array2};
+use ort::session::builder::GraphOptimizationLevel;
+use ort::session::Session;
+use ort::value::Tensor;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tokenizers::{PaddingParams, Tokenizer, TruncationParams};
+
+// Local files and runtime options for the ONNX backend
+#[derive(Debug, Clone)]
+pub struct OnnxConfig {
+    pub model_path: PathBuf,
+    pub tokenizer_path: PathBuf,
+    pub runtime_library: Option<PathBuf>, // onnxruntime shared library, otherwise ORT_DYLIB_PATH
+    pub model_id: String,
+    pub max_length: usize,
+    pub intra_threads: usize,
+}
+
+impl OnnxConfig {
+    pub fn new(model_path: impl Into<PathBuf>, tokenizer_path: impl Into<PathBuf>) -> Self {
+        OnnxConfig {
+            model_path: model_path.into(),
+            tokenizer_path: tokenizer_path.into(),
+            runtime_library: None,
+            model_id: OnnxEmbedder::DEFAULT_MODEL_ID.to_string(),
+            max_length: 256,
+            intra_threads: 1,
+        }
+    }
+}
+
+// Mean-pooled transformer sentence embeddings computed on the CPU
+pub struct OnnxEmbedder {
+    session: Mutex<Session>,
+    tokenizer: Tokenizer,
+    model_id: String,
+    dimension: usize,
+    uses_token_type_ids: bool,
+}
+
+impl OnnxEmbedder {
+    pub const DEFAULT_MODEL_ID: &'static str = "all-MiniLM-L6-v2";
+    pub const DEFAULT_DIMENSION: usize = 384;
+
+    // Loads model and tokenizer from local paths; nothing is downloaded
+    pub fn new(model_path: impl Into<PathBuf>, tokenizer_path: impl Into<PathBuf>) -> Result<Self> {
+        Self::from_config(OnnxConfig::new(model_path, tokenizer_path))
+    }
+
+    pub fn from_config(config: OnnxConfig) -> Result<Self> {
+        if let Some(library) = &config.runtime_library {
+            ort::init_from(library.display().to_string())
+                .commit()
+                .map_err(|e| anyhow!("failed to load onnxruntime from {}: {e}", library.display()))?;
+        }
+
+        let mut tokenizer = Tokenizer::from_file(&config.tokenizer_path)
+            .map_err(|e| anyhow!("failed to load tokenizer {}: {e}", config.tokenizer_path.display()))?;
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: config.max_length,
+                ..Default::default()
+            }))
+            .map_err(|e| anyhow!("invalid truncation settings: {e}"))?;
+        tokenizer.with_padding(Some(PaddingParams::default()));
+
+        let session = Session::builder()
+            .and_then(|builder| builder.with_optimization_level(GraphOptimizationLevel::Level3))
+            .and_then(|builder| builder.with_intra_threads(config.intra_threads))
+            .and_then(|builder| builder.commit_from_file(&config.model_path))
+            .map_err(|e| anyhow!("failed to load ONNX model {}: {e}", config.model_path.display()))?;
+        let uses_token_type_ids = session.inputs.iter().any(|input| input.name == "token_type_ids");
+
+        let mut embedder = OnnxEmbedder {
+            session: Mutex::new(session),
+            tokenizer,
+            model_id: config.model_id,
+            dimension: Self::DEFAULT_DIMENSION,
+            uses_token_type_ids,
+        };
+
+        // Probe once so the reported dimension matches the loaded model
+        let probe = embedder.run(&["dimension probe"]).context("ONNX model probe failed")?;
+        embedder.dimension = probe.ncols();
+        Ok(embedder)
+    }
+
+    fn run(&self, texts: &[&str]) -> Result<Array2<f32>> {
+        let encodings = self.tokenizer
+            .encode_batch(texts.to_vec(), true)
+            .map_err(|e| anyhow!("tokenization failed: {e}"))?;
+        let batch = encodings.len();
+        let seq_len = encodings.first().map(|e| e.get_ids().len()).unwrap_or(0);
+
+        let mut input_ids = Vec::with_capacity(batch * seq_len);
+        let mut attention_mask = Vec::with_capacity(batch * seq_len);
+        let mut token_type_ids = Vec::with_capacity(batch * seq_len);
+        for encoding in &encodings {
+            input_ids.extend(encoding.get_ids().iter().map(|&id| id as i64));
+            attention_mask.extend(encoding.get_attention_mask().iter().map(|&m| m as i64));
+            token_type_ids.extend(encoding.get_type_ids().iter().map(|&t| t as i64));
+        }
+        let shape = [batch, seq_len];
+
+        let mut session = self.session.lock().map_err(|_| anyhow!("ONNX session lock poisoned"))?;
+        let outputs = if self.uses_token_type_ids {
+            session.run(ort::inputs![
+                "input_ids" => Tensor::from_array((shape, input_ids))?,
+                "attention_mask" => Tensor::from_array((shape, attention_mask.clone()))?,
+                "token_type_ids" => Tensor::from_array((shape, token_type_ids))?,
+            ])?
+        } else {
+            session.run(ort::inputs![
+                "input_ids" => Tensor::from_array((shape, input_ids))?,
+                "attention_mask" => Tensor::from_array((shape, attention_mask.clone()))?,
+            ])?
+        };
+
+        let (output_shape, hidden) = outputs[0].try_extract_tensor::<f32>()?;
+        if output_shape.len() != 3 || output_shape[0] as usize != batch || output_shape[1] as usize != seq_len {
+            bail!("unexpected ONNX output shape {output_shape:?}, expected [batch, tokens, hidden]");
+        }
+        let hidden_size = output_shape[2] as usize;
+
+        // Mean pooling over non-padding tokens, then L2 normalization
+        let mut pooled = Array2::<f32>::zeros((batch, hidden_size));
+        for (b, mut row) in pooled.rows_mut().into_iter().enumerate() {
+            let mut count = 0.0f32;
+            for t in 0..seq_len {
+                if attention_mask[b * seq_len + t] == 0 {
+                    continue;
+                }
+                let offset = (b * seq_len + t) * hidden_size;
+                for (value, &h) in row.iter_mut().zip(&hidden[offset..offset + hidden_size]) {
+                    *value += h;
+                }
+                count += 1.0;
+            }
+            if count > 0.0 {
+                row.mapv_inplace(|x| x / count);
+            }
+            let norm = row.dot(&row).sqrt();
+            if norm > 0.0 {
+                row.mapv_inplace(|x| x / norm);
+            }
+        }
+        Ok(pooled)
+    }
+}
+
+impl Embedder for OnnxEmbedder {
+    fn embed(&self, text: &str) -> Result<Array1<f32>> {
+        let batch = self.run(&[text])?;
+        Ok(batch.row(0).to_owned())
+    }
+
+    fn embed_batch(&self, texts: &[&str]) -> Result<Array2<f32>> {
+        if texts.is_empty() {
+            return Ok(Array2::zeros((0, self.dimension)));
+        }
+        self.run(texts)
+    }
+
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+}
@@ -0,0 +1,190 @@
+// #9400D3 Dimensionality Reduction Helpers
+use super::Embedder;
+use anyhow::{bail, Result};
+use ndarray::{Array1, Array2, Axis};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// Adapter that maps another embedder's output into a different dimension
+pub struct ProjectedEmbedder<E: Embedder> {
+    inner: E,
+    projection: Array2<f32>,
+    model_id: String,
+}
+
+impl<E: Embedder> ProjectedEmbedder<E> {
+    // Seeded sparse random projection down (or up) to `dimension`
+    pub fn random(inner: E, dimension: usize, seed: u64) -> Self {
+        let projection = random_projection(inner.dimension(), dimension, seed);
+        let model_id = format!("{}+rp{dimension}-s{seed}", inner.model_id());
+        ProjectedEmbedder { inner, projection, model_id }
+    }
+
+    // Explicit (inner dimension x target dimension) mapping, e.g. a learned adapter
+    pub fn from_matrix(inner: E, projection: Array2<f32>, name: &str) -> Result<Self> {
+        if projection.nrows() != inner.dimension() {
+            bail!(
+                "projection has {} rows but embedder '{}' produces {} values",
+                projection.nrows(), inner.model_id(), inner.dimension()
+            );
+        }
+        let model_id = format!("{}+{name}", inner.model_id());
+        Ok(ProjectedEmbedder { inner, projection, model_id })
+    }
+
+    pub fn inner(&self) -> &E {
+        &self.inner
+    }
+}
+
+impl<E: Embedder> Embedder for ProjectedEmbedder<E> {
+    fn embed(&self, text: &str) -> Result<Array1<f32>> {
+        let mut vector = self.inner.embed(text)?.dot(&self.projection);
+        let norm = vector.dot(&vector).sqrt();
+        if norm > 0.0 {
+            vector.mapv_inplace(|x| x / norm);
+        }
+        Ok(vector)
+    }
+
+    fn embed_batch(&self, texts: &[&str]) -> Result<Array2<f32>> {
+        let mut batch = self.inner.embed_batch(texts)?.dot(&self.projection);
+        normalize_rows(&mut batch);
+        Ok(batch)
+    }
+
+    fn dimension(&self) -> usize {
+        self.projection.ncols()
+    }
+
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+}
+
+// Sparse random projection (Achlioptas) mapping `input_dim` to `output_dim`
+pub fn random_projection(input_dim: usize, output_dim: usize, seed: u64) -> Array2<f32> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let scale = (3.0 / output_dim.max(1) as f32).sqrt();
+    Array2::from_shape_fn((input_dim, output_dim), |_| {
+        match rng.gen_range(0..6) {
+            0 => scale,
+            1 => -scale,
+            _ => 0.0,
+        }
+    })
+}
+
+// Top-`k` right singular vectors of `matrix` as a (cols x k) projection.
+// Uses randomized subspace iteration, so it stays cheap for wide sparse-ish inputs.
+// A matrix of lower rank than `k` leaves the extra columns zero, so the output keeps the requested size.
+pub fn truncated_svd(matrix: &Array2<f32>, k: usize, seed: u64) -> Array2<f32> {
+    let cols = matrix.ncols();
+    let requested = k;
+    let k = k.min(cols).min(matrix.nrows()).max(1);
+    let oversampled = (k + 8).min(cols);
+
+    // Range finder: Q spans the dominant column space of X
+    let mut rng = StdRng::seed_from_u64(seed);
+    let omega = Array2::from_shape_fn((cols, oversampled), |_| rng.gen_range(-1.0f32..1.0));
+    let mut q = orthonormalize(matrix.dot(&omega));
+    for _ in 0..4 {
+        let z = orthonormalize(matrix.t().dot(&q));
+        q = orthonormalize(matrix.dot(&z));
+    }
+
+    // B = Qᵀ X is small; its right singular vectors come from eig(B Bᵀ)
+    let b = q.t().dot(matrix);
+    let (eigenvalues, eigenvectors) = symmetric_eigen(&b.dot(&b.t()));
+
+    let mut order: Vec<usize> = (0..eigenvalues.len()).collect();
+    order.sort_by(|&i, &j| eigenvalues[j].partial_cmp(&eigenvalues[i]).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut components = Array2::zeros((cols, requested));
+    for (out, &idx) in order.iter().take(k).enumerate() {
+        let sigma = eigenvalues[idx].max(0.0).sqrt();
+        if sigma <= f32::EPSILON {
+            continue;
+        }
+        let v = b.t().dot(&eigenvectors.column(idx)) / sigma;
+        components.column_mut(out).assign(&v);
+    }
+    components
+}
+
+// Modified Gram-Schmidt over the columns of `m`
+fn orthonormalize(mut m: Array2<f32>) -> Array2<f32> {
+    for j in 0..m.ncols() {
+        for i in 0..j {
+            let prev = m.column(i).to_owned();
+            let proj = prev.dot(&m.column(j));
+            m.column_mut(j).scaled_add(-proj, &prev);
+        }
+        let norm = m.column(j).dot(&m.column(j)).sqrt();
+        if norm > 1e-8 {
+            m.column_mut(j).mapv_inplace(|x| x / norm);
+        } else {
+            m.column_mut(j).fill(0.0);
+        }
+    }
+    m
+}
+
+// Cyclic Jacobi eigen-decomposition for small symmetric matrices
+fn symmetric_eigen(matrix: &Array2<f32>) -> (Array1<f32>, Array2<f32>) {
+    let n = matrix.nrows();
+    let mut a = matrix.mapv(f64::from);
+    let mut v = Array2::<f64>::eye(n);
+
+    for _ in 0..100 {
+        let off: f64 = (0..n)
+            .flat_map(|i| (0..n).filter(move |&j| j != i).map(move |j| (i, j)))
+            .map(|(i, j)| a[[i, j]] * a[[i, j]])
+            .sum();
+        if off < 1e-18 {
+            break;
+        }
+        for p in 0..n {
+            for q in (p + 1)..n {
+                if a[[p, q]].abs() < 1e-20 {
+                    continue;
+                }
+                let theta = (a[[q, q]] - a[[p, p]]) / (2.0 * a[[p, q]]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+
+                for k in 0..n {
+                    let akp = a[[k, p]];
+                    let akq = a[[k, q]];
+                    a[[k, p]] = c * akp - s * akq;
+                    a[[k, q]] = s * akp + c * akq;
+                }
+                for k in 0..n {
+                    let apk = a[[p, k]];
+                    let aqk = a[[q, k]];
+                    a[[p, k]] = c * apk - s * aqk;
+                    a[[q, k]] = s * apk + c * aqk;
+                }
+                for k in 0..n {
+                    let vkp = v[[k, p]];
+                    let vkq = v[[k, q]];
+                    v[[k, p]] = c * vkp - s * vkq;
+                    v[[k, q]] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+
+    (a.diag().mapv(|x| x as f32), v.mapv(|x| x as f32))
+}
+
+// Scales every row of `m` to unit length, leaving zero rows untouched
+pub(crate) fn normalize_rows(m: &mut Array2<f32>) {
+    for mut row in m.axis_iter_mut(Axis(0)) {
+        let norm = row.dot(&row).sqrt();
+        if norm > 0.0 {
+            row.mapv_inplace(|x| x / norm);
+        }
+    }
+}
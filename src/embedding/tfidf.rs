@@ -0,0 +1,265 @@
+// #9400D3 TF-IDF Corpus-Fitted Embedder
+use super::hashing::{fnv1a, tokenize};
+use super::projection::{normalize_rows, random_projection, truncated_svd};
+use super::Embedder;
+use anyhow::{bail, Context, Result};
+use ndarray::{Array1, Array2};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// How the sparse TF-IDF space is reduced to a fixed dimension
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Reduction {
+    None,
+    RandomProjection { dimension: usize, seed: u64 },
+    TruncatedSvd { dimension: usize, seed: u64 },
+}
+
+#[derive(Debug, Clone)]
+pub struct TfidfConfig {
+    pub min_df: usize,
+    pub max_features: usize,
+    pub use_bigrams: bool,
+    pub extensions: Vec<String>,
+    pub reduction: Reduction,
+}
+
+impl Default for TfidfConfig {
+    fn default() -> Self {
+        TfidfConfig {
+            min_df: 1,
+            max_features: 20_000,
+            use_bigrams: true,
+            extensions: vec!["txt".to_string(), "md".to_string()],
+            reduction: Reduction::TruncatedSvd { dimension: 128, seed: 42 },
+        }
+    }
+}
+
+// Everything needed to reproduce embeddings; this is what gets versioned on disk
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TfidfModel {
+    pub vocabulary: BTreeMap<String, usize>,
+    pub idf: Vec<f32>,
+    pub use_bigrams: bool,
+    pub reduction: Reduction,
+    pub projection: Option<Array2<f32>>,
+    pub document_count: usize,
+}
+
+pub struct TfidfEmbedder {
+    model: TfidfModel,
+    model_id: String,
+}
+
+impl TfidfEmbedder {
+    // Fits on every file under `dir` whose extension is listed in the config
+    pub fn fit_dir(dir: impl AsRef<Path>, config: &TfidfConfig) -> Result<Self> {
+        let mut paths = Vec::new();
+        collect_files(dir.as_ref(), &config.extensions, &mut paths)?;
+        paths.sort();
+        if paths.is_empty() {
+            bail!("no corpus files found under {}", dir.as_ref().display());
+        }
+
+        let documents = paths.iter()
+            .map(|path| fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display())))
+            .collect::<Result<Vec<_>>>()?;
+        let documents: Vec<&str> = documents.iter().map(String::as_str).collect();
+        Self::fit(&documents, config)
+    }
+
+    pub fn fit(documents: &[&str], config: &TfidfConfig) -> Result<Self> {
+        if documents.is_empty() {
+            bail!("cannot fit a TF-IDF model on an empty corpus");
+        }
+        if let Reduction::RandomProjection { dimension: 0, .. } | Reduction::TruncatedSvd { dimension: 0, .. } = config.reduction {
+            bail!("cannot reduce a TF-IDF model to zero dimensions");
+        }
+
+        // Document frequency of every term
+        let mut document_frequency: HashMap<String, usize> = HashMap::new();
+        for document in documents {
+            let mut seen: Vec<String> = terms(document, config.use_bigrams);
+            seen.sort();
+            seen.dedup();
+            for term in seen {
+                *document_frequency.entry(term).or_insert(0) += 1;
+            }
+        }
+
+        // Keep the most frequent terms, breaking ties alphabetically for reproducibility
+        let mut kept: Vec<(String, usize)> = document_frequency.into_iter()
+            .filter(|(_, df)| *df >= config.min_df)
+            .collect();
+        kept.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        kept.truncate(config.max_features);
+        kept.sort_by(|a, b| a.0.cmp(&b.0));
+        if kept.is_empty() {
+            bail!("corpus produced an empty vocabulary (min_df = {})", config.min_df);
+        }
+
+        let n = documents.len() as f32;
+        let vocabulary = kept.iter().enumerate().map(|(i, (term, _))| (term.clone(), i)).collect();
+        let idf = kept.iter().map(|(_, df)| ((1.0 + n) / (1.0 + *df as f32)).ln() + 1.0).collect();
+
+        let mut model = TfidfModel {
+            vocabulary,
+            idf,
+            use_bigrams: config.use_bigrams,
+            reduction: config.reduction,
+            projection: None,
+            document_count: documents.len(),
+        };
+
+        model.projection = match config.reduction {
+            Reduction::None => None,
+            Reduction::RandomProjection { dimension, seed } => {
+                Some(random_projection(model.idf.len(), dimension, seed))
+            }
+            Reduction::TruncatedSvd { dimension, seed } => {
+                let mut matrix = Array2::zeros((documents.len(), model.idf.len()));
+                for (mut row, document) in matrix.rows_mut().into_iter().zip(documents) {
+                    row.assign(&model.sparse_vector(document));
+                }
+                normalize_rows(&mut matrix);
+                Some(truncated_svd(&matrix, dimension, seed))
+            }
+        };
+
+        Ok(Self::from_model(model))
+    }
+
+    pub fn from_model(model: TfidfModel) -> Self {
+        // The id fingerprints the vocabulary, weights and projection so caches never mix fitted models
+        let mut fingerprint = 0u64;
+        for (term, index) in &model.vocabulary {
+            fingerprint = fnv1a(fingerprint, term.as_bytes());
+            fingerprint = fnv1a(fingerprint, &index.to_le_bytes());
+        }
+        for weight in &model.idf {
+            fingerprint = fnv1a(fingerprint, &weight.to_le_bytes());
+        }
+        fingerprint = fnv1a(fingerprint, &[u8::from(model.use_bigrams)]);
+        for weight in model.projection.iter().flatten() {
+            fingerprint = fnv1a(fingerprint, &weight.to_le_bytes());
+        }
+        let dimension = model.projection.as_ref().map(|p| p.ncols()).unwrap_or(model.idf.len());
+        let model_id = format!("tfidf-d{dimension}-{fingerprint:016x}");
+        TfidfEmbedder { model, model_id }
+    }
+
+    pub fn model(&self) -> &TfidfModel {
+        &self.model
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let json = serde_json::to_string(&self.model)?;
+        fs::write(path.as_ref(), json)
+            .with_context(|| format!("failed to write TF-IDF model to {}", path.as_ref().display()))
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let json = fs::read_to_string(path.as_ref())
+            .with_context(|| format!("failed to read TF-IDF model from {}", path.as_ref().display()))?;
+        let model: TfidfModel = serde_json::from_str(&json)?;
+        if model.vocabulary.len() != model.idf.len() {
+            bail!("corrupt TF-IDF model: {} terms but {} idf weights", model.vocabulary.len(), model.idf.len());
+        }
+        if let Some((term, index)) = model.vocabulary.iter().find(|(_, &index)| index >= model.idf.len()) {
+            bail!("corrupt TF-IDF model: term '{term}' points at index {index} of {} idf weights", model.idf.len());
+        }
+        let mut seen = HashSet::new();
+        if let Some((term, index)) = model.vocabulary.iter().find(|(_, &index)| !seen.insert(index)) {
+            bail!("corrupt TF-IDF model: vocabulary term '{term}' reuses index {index}");
+        }
+        if let Some(projection) = &model.projection {
+            if projection.nrows() != model.idf.len() {
+                bail!("corrupt TF-IDF model: projection has {} rows for {} terms", projection.nrows(), model.idf.len());
+            }
+        }
+        match (model.reduction, &model.projection) {
+            (Reduction::None, None) => {}
+            (Reduction::None, Some(_)) => bail!("corrupt TF-IDF model: projection present but reduction is None"),
+            (Reduction::RandomProjection { dimension, .. } | Reduction::TruncatedSvd { dimension, .. }, projection) => {
+                let columns = projection.as_ref().map_or(0, |p| p.ncols());
+                if columns != dimension {
+                    bail!("corrupt TF-IDF model: projection has {columns} columns but reduction.dimension is {dimension}");
+                }
+            }
+        }
+        Ok(Self::from_model(model))
+    }
+}
+
+impl TfidfModel {
+    // Sublinear TF times IDF in vocabulary space, L2-normalized
+    fn sparse_vector(&self, text: &str) -> Array1<f32> {
+        let mut counts: HashMap<usize, f32> = HashMap::new();
+        for term in terms(text, self.use_bigrams) {
+            if let Some(&index) = self.vocabulary.get(&term) {
+                *counts.entry(index).or_insert(0.0) += 1.0;
+            }
+        }
+
+        let mut vector = Array1::zeros(self.idf.len());
+        for (index, count) in counts {
+            vector[index] = (1.0 + count.ln()) * self.idf[index];
+        }
+        let norm = vector.dot(&vector).sqrt();
+        if norm > 0.0 {
+            vector.mapv_inplace(|x| x / norm);
+        }
+        vector
+    }
+}
+
+impl Embedder for TfidfEmbedder {
+    fn embed(&self, text: &str) -> Result<Array1<f32>> {
+        let sparse = self.model.sparse_vector(text);
+        let Some(projection) = &self.model.projection else {
+            return Ok(sparse);
+        };
+
+        let mut vector = sparse.dot(projection);
+        let norm = vector.dot(&vector).sqrt();
+        if norm > 0.0 {
+            vector.mapv_inplace(|x| x / norm);
+        }
+        Ok(vector)
+    }
+
+    fn dimension(&self) -> usize {
+        self.model.projection.as_ref().map(|p| p.ncols()).unwrap_or(self.model.idf.len())
+    }
+
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+}
+
+fn terms(text: &str, use_bigrams: bool) -> Vec<String> {
+    let words = tokenize(text);
+    let mut terms = words.clone();
+    if use_bigrams {
+        terms.extend(words.windows(2).map(|pair| format!("{} {}", pair[0], pair[1])));
+    }
+    terms
+}
+
+fn collect_files(dir: &Path, extensions: &[String], out: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir).with_context(|| format!("failed to read corpus directory {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, extensions, out)?;
+        } else if path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| extensions.iter().any(|allowed| allowed.eq_ignore_ascii_case(ext)))
+        {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,219 @@
+// #FFD700 Graph Export (Graphviz DOT / GraphML)
+use crate::agent_orchestrator::AgentOrchestrator;
+use crate::concepts::{cosine_similarity, SimilarityEngine};
+use crate::knowledge_graph::{EdgeKind, NodeKey};
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExportOptions {
+    pub similarity_cutoff: f32, // Concept pairs at or below this cosine similarity get no edge
+    pub include_modules: bool,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        ExportOptions { similarity_cutoff: 0.5, include_modules: true }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConceptNode {
+    pub name: String,
+    pub curiosity: f32,
+    pub threshold: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModuleNode {
+    pub name: String,
+    pub co_creations: u32,
+    pub members: Vec<String>, // Concepts the module holds
+}
+
+// Snapshot of concepts, modules and their links, ready to render
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConceptGraph {
+    pub concepts: Vec<ConceptNode>,
+    pub modules: Vec<ModuleNode>,
+    pub similarities: Vec<(String, String, f32)>,
+    pub co_creations: Vec<(String, String, u64)>, // Module pairs and how often they co-created
+}
+
+impl ConceptGraph {
+    pub fn build(engine: &SimilarityEngine, orchestrator: &AgentOrchestrator, options: ExportOptions) -> Self {
+        let concepts = engine.concepts().iter()
+            .map(|c| ConceptNode { name: c.name.clone(), curiosity: c.curiosity_score, threshold: c.threshold })
+            .collect();
+
+        let mut similarities = Vec::new();
+        for (i, a) in engine.concepts().iter().enumerate() {
+            for b in engine.concepts().iter().skip(i + 1).filter(|b| b.vector.len() == a.vector.len()) {
+                let similarity = cosine_similarity(&a.vector, &b.vector);
+                if similarity > options.similarity_cutoff {
+                    similarities.push((a.name.clone(), b.name.clone(), similarity));
+                }
+            }
+        }
+
+        // HashMap order is arbitrary; sort so exports diff cleanly
+        let mut modules: Vec<ModuleNode> = Vec::new();
+        let mut co_creations = Vec::new();
+        if options.include_modules {
+            modules = orchestrator.modules.values()
+                .map(|m| ModuleNode {
+                    name: m.name.clone(),
+                    co_creations: m.co_creation_count,
+                    members: m.concepts().into_iter().map(|c| c.name).collect(),
+                })
+                .collect();
+            modules.sort_by(|a, b| a.name.cmp(&b.name));
+
+            co_creations = orchestrator.knowledge_graph.edges().iter()
+                .filter(|e| e.kind == EdgeKind::CoCreated)
+                .filter_map(|e| match (&e.from, &e.to) {
+                    (NodeKey::Module(a), NodeKey::Module(b)) => Some((a.clone(), b.clone(), e.count)),
+                    _ => None,
+                })
+                .collect();
+            co_creations.sort();
+        }
+
+        ConceptGraph { concepts, modules, similarities, co_creations }
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("graph starweave {\n  layout=neato;\n  overlap=false;\n");
+        dot.push_str("  node [style=filled, fontname=\"Helvetica\"];\n");
+        for concept in &self.concepts {
+            let size = node_size(concept.curiosity);
+            let _ = writeln!(
+                dot,
+                "  \"{}\" [shape=circle, fillcolor=\"#FF69B4\", width={size:.2}, fixedsize=true, tooltip=\"curiosity {:.2}, threshold {:.2}\"];",
+                dot_escape(&concept.name), concept.curiosity, concept.threshold
+            );
+        }
+        for module in &self.modules {
+            let id = format!("module:{}", module.name);
+            let _ = writeln!(
+                dot,
+                "  \"{}\" [shape=box, fillcolor=\"#ADD8E6\", label=\"{}\\n{} co-creations\"];",
+                dot_escape(&id), dot_escape(&module.name), module.co_creations
+            );
+            for member in &module.members {
+                let _ = writeln!(dot, "  \"{}\" -- \"{}\" [style=dotted];", dot_escape(&id), dot_escape(member));
+            }
+        }
+        for (a, b, similarity) in &self.similarities {
+            let _ = writeln!(
+                dot,
+                "  \"{}\" -- \"{}\" [label=\"{similarity:.2}\", penwidth={:.1}];",
+                dot_escape(a), dot_escape(b), 1.0 + 3.0 * similarity.max(0.0)
+            );
+        }
+        for (a, b, count) in &self.co_creations {
+            let _ = writeln!(
+                dot,
+                "  \"module:{}\" -- \"module:{}\" [color=\"#FFA07A\", label=\"{count}×\", penwidth={}];",
+                dot_escape(a), dot_escape(b), 1 + (*count).min(9)
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    pub fn to_graphml(&self) -> String {
+        let mut xml = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"kind\" for=\"all\" attr.name=\"kind\" attr.type=\"string\"/>\n",
+            "  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n",
+            "  <key id=\"curiosity\" for=\"node\" attr.name=\"curiosity\" attr.type=\"double\"/>\n",
+            "  <key id=\"size\" for=\"node\" attr.name=\"size\" attr.type=\"double\"/>\n",
+            "  <key id=\"threshold\" for=\"node\" attr.name=\"threshold\" attr.type=\"double\"/>\n",
+            "  <key id=\"co_creations\" for=\"node\" attr.name=\"co_creations\" attr.type=\"int\"/>\n",
+            "  <key id=\"weight\" for=\"edge\" attr.name=\"weight\" attr.type=\"double\"/>\n",
+            "  <graph id=\"starweave\" edgedefault=\"undirected\">\n",
+        ));
+
+        let mut ids: BTreeMap<String, String> = BTreeMap::new();
+        for (i, concept) in self.concepts.iter().enumerate() {
+            let id = format!("c{i}");
+            ids.insert(concept.name.clone(), id.clone());
+            let _ = writeln!(
+                xml,
+                "    <node id=\"{id}\"><data key=\"kind\">concept</data><data key=\"label\">{}</data>\
+                 <data key=\"curiosity\">{}</data><data key=\"size\">{:.3}</data><data key=\"threshold\">{}</data></node>",
+                xml_escape(&concept.name), concept.curiosity, node_size(concept.curiosity), concept.threshold
+            );
+        }
+        for (i, module) in self.modules.iter().enumerate() {
+            let id = format!("m{i}");
+            ids.insert(format!("module:{}", module.name), id.clone());
+            let _ = writeln!(
+                xml,
+                "    <node id=\"{id}\"><data key=\"kind\">module</data><data key=\"label\">{}</data>\
+                 <data key=\"co_creations\">{}</data></node>",
+                xml_escape(&module.name), module.co_creations
+            );
+        }
+
+        let mut edge = 0;
+        let mut push_edge = |xml: &mut String, from: Option<&String>, to: Option<&String>, kind: &str, weight: f64| {
+            if let (Some(from), Some(to)) = (from, to) {
+                let _ = writeln!(
+                    xml,
+                    "    <edge id=\"e{edge}\" source=\"{from}\" target=\"{to}\"><data key=\"kind\">{kind}</data>\
+                     <data key=\"weight\">{weight}</data></edge>"
+                );
+                edge += 1;
+            }
+        };
+        for module in &self.modules {
+            for member in &module.members {
+                push_edge(&mut xml, ids.get(&format!("module:{}", module.name)), ids.get(member), "member", 1.0);
+            }
+        }
+        for (a, b, similarity) in &self.similarities {
+            push_edge(&mut xml, ids.get(a), ids.get(b), "similarity", f64::from(*similarity));
+        }
+        for (a, b, count) in &self.co_creations {
+            push_edge(&mut xml, ids.get(&format!("module:{a}")), ids.get(&format!("module:{b}")), "co_created", *count as f64);
+        }
+
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+
+    // Format follows the extension: .dot / .gv or .graphml
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default().to_ascii_lowercase();
+        let text = match extension.as_str() {
+            "dot" | "gv" => self.to_dot(),
+            "graphml" => self.to_graphml(),
+            _ => bail!("{}: export files must end in .dot, .gv or .graphml", path.display()),
+        };
+        fs::write(path, text).with_context(|| format!("failed to write {}", path.display()))
+    }
+}
+
+// Diameter in inches for DOT, also exported to GraphML as a hint
+fn node_size(curiosity: f32) -> f32 {
+    0.5 + 1.5 * curiosity.clamp(0.0, 1.0)
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
@@ -0,0 +1,266 @@
+// #00CED1 Knowledge Graph (grown by autonomous actions)
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Identity of a node; inputs are keyed by their normalized text. Modules get their
+// own kind since they are often named after the concept they hold
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(tag = "kind", content = "id", rename_all = "snake_case")]
+pub enum NodeKey {
+    Concept(String),
+    Module(String),
+    Input(String),
+    Episode(u64), // One matched interaction
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeKind {
+    CoOccurred,  // Appeared in the same episode (undirected)
+    SuggestedBy, // Concept → module that proposed it
+    CoCreated,   // Modules that worked on an input together (undirected)
+}
+
+impl EdgeKind {
+    fn is_directed(&self) -> bool {
+        matches!(self, EdgeKind::SuggestedBy)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct GraphNode {
+    pub key: NodeKey,
+    pub visits: u64,
+    pub first_seen: u64,
+    pub last_seen: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct GraphEdge {
+    pub from: NodeKey,
+    pub to: NodeKey,
+    pub kind: EdgeKind,
+    pub weight: f32, // Grows with every repeat of the relation
+    pub count: u64,
+}
+
+// One step away from a node
+#[derive(Debug, Clone, PartialEq)]
+pub struct Neighbour {
+    pub key: NodeKey,
+    pub kind: EdgeKind,
+    pub weight: f32,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct KnowledgeGraph {
+    nodes: Vec<GraphNode>,
+    edges: Vec<GraphEdge>,
+    next_episode: u64,
+    #[serde(skip)]
+    node_index: HashMap<NodeKey, usize>,
+    #[serde(skip)]
+    edge_index: HashMap<(NodeKey, NodeKey, EdgeKind), usize>,
+}
+
+impl KnowledgeGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn nodes(&self) -> &[GraphNode] {
+        &self.nodes
+    }
+
+    pub fn edges(&self) -> &[GraphEdge] {
+        &self.edges
+    }
+
+    pub fn node(&self, key: &NodeKey) -> Option<&GraphNode> {
+        self.node_index.get(key).map(|&i| &self.nodes[i])
+    }
+
+    // Adds the node if new and bumps its visit count
+    pub fn touch(&mut self, key: NodeKey) {
+        let now = now();
+        match self.node_index.get(&key) {
+            Some(&i) => {
+                self.nodes[i].visits += 1;
+                self.nodes[i].last_seen = now;
+            }
+            None => {
+                self.node_index.insert(key.clone(), self.nodes.len());
+                self.nodes.push(GraphNode { key, visits: 1, first_seen: now, last_seen: now });
+            }
+        }
+    }
+
+    // Strengthens (or creates) an edge; undirected kinds are stored once per pair
+    pub fn connect(&mut self, from: NodeKey, to: NodeKey, kind: EdgeKind, weight: f32) {
+        let (from, to) = if !kind.is_directed() && to < from { (to, from) } else { (from, to) };
+        for key in [&from, &to] {
+            if !self.node_index.contains_key(key) {
+                self.touch(key.clone());
+            }
+        }
+        let edge_key = (from, to, kind);
+        match self.edge_index.get(&edge_key) {
+            Some(&i) => {
+                self.edges[i].weight += weight;
+                self.edges[i].count += 1;
+            }
+            None => {
+                let (from, to, kind) = edge_key.clone();
+                self.edge_index.insert(edge_key, self.edges.len());
+                self.edges.push(GraphEdge { from, to, kind, weight, count: 1 });
+            }
+        }
+    }
+
+    // Links a concept match to its input through a new episode node; returns the episode id
+    pub fn record_episode(&mut self, concept: &str, input: &str) -> u64 {
+        let episode = NodeKey::Episode(self.next_episode);
+        self.next_episode += 1;
+        let concept = NodeKey::Concept(concept.to_string());
+        let input = NodeKey::Input(normalize_input(input));
+
+        for key in [&episode, &concept, &input] {
+            self.touch(key.clone());
+        }
+        self.connect(episode.clone(), concept.clone(), EdgeKind::CoOccurred, 1.0);
+        self.connect(episode.clone(), input.clone(), EdgeKind::CoOccurred, 1.0);
+        self.connect(concept, input, EdgeKind::CoOccurred, 1.0);
+        self.next_episode - 1
+    }
+
+    // Notes an input no concept matched; it stays unlinked until a later match
+    pub fn record_input(&mut self, input: &str) {
+        self.touch(NodeKey::Input(normalize_input(input)));
+    }
+
+    pub fn record_suggestion(&mut self, suggested: &str, by_module: &str) {
+        self.connect(
+            NodeKey::Concept(suggested.to_string()),
+            NodeKey::Module(by_module.to_string()),
+            EdgeKind::SuggestedBy,
+            1.0,
+        );
+    }
+
+    pub fn record_co_creation(&mut self, primary: &str, partner: &str) {
+        self.connect(
+            NodeKey::Module(primary.to_string()),
+            NodeKey::Module(partner.to_string()),
+            EdgeKind::CoCreated,
+            1.0,
+        );
+    }
+
+    // Direct neighbours in either direction, strongest first
+    pub fn neighbours(&self, key: &NodeKey) -> Vec<Neighbour> {
+        let mut neighbours: Vec<Neighbour> = self.edges.iter()
+            .filter_map(|edge| {
+                let other = if &edge.from == key {
+                    &edge.to
+                } else if &edge.to == key {
+                    &edge.from
+                } else {
+                    return None;
+                };
+                Some(Neighbour { key: other.clone(), kind: edge.kind, weight: edge.weight })
+            })
+            .collect();
+        neighbours.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+        neighbours
+    }
+
+    // Every node within `depth` hops, with its distance, nearest first
+    pub fn neighbourhood(&self, key: &NodeKey, depth: usize) -> Vec<(NodeKey, usize)> {
+        let mut seen = HashSet::from([key.clone()]);
+        let mut queue = VecDeque::from([(key.clone(), 0)]);
+        let mut found = Vec::new();
+        while let Some((current, distance)) = queue.pop_front() {
+            if distance == depth {
+                continue;
+            }
+            for neighbour in self.neighbours(&current) {
+                if seen.insert(neighbour.key.clone()) {
+                    found.push((neighbour.key.clone(), distance + 1));
+                    queue.push_back((neighbour.key, distance + 1));
+                }
+            }
+        }
+        found
+    }
+
+    // Concepts sharing inputs or collaborations with `concept`, ranked by total edge weight
+    pub fn related_concepts(&self, concept: &str, k: usize) -> Vec<(String, f32)> {
+        let start = NodeKey::Concept(concept.to_string());
+        let mut scores: HashMap<String, f32> = HashMap::new();
+        for first in self.neighbours(&start) {
+            if let NodeKey::Concept(name) = &first.key {
+                *scores.entry(name.clone()).or_default() += first.weight;
+                continue;
+            }
+            for second in self.neighbours(&first.key) {
+                match &second.key {
+                    NodeKey::Concept(name) if name != concept => {
+                        *scores.entry(name.clone()).or_default() += first.weight.min(second.weight);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        let mut related: Vec<(String, f32)> = scores.into_iter().collect();
+        related.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        related.truncate(k);
+        related
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(text: &str) -> Result<Self> {
+        let mut graph: KnowledgeGraph = serde_json::from_str(text)?;
+        graph.node_index = graph.nodes.iter().enumerate().map(|(i, n)| (n.key.clone(), i)).collect();
+        graph.edge_index = graph.edges.iter().enumerate()
+            .map(|(i, e)| ((e.from.clone(), e.to.clone(), e.kind), i))
+            .collect();
+        Ok(graph)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        fs::write(path, self.to_json()?)
+            .with_context(|| format!("failed to write knowledge graph {}", path.display()))
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("failed to read knowledge graph {}", path.display()))?;
+        Self::from_json(&text)
+    }
+}
+
+// Inputs that differ only in case or spacing share a node
+fn normalize_input(input: &str) -> String {
+    input.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
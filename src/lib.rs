@@ -6,11 +6,14 @@ pub mod actions;
 pub mod state;
 pub mod module_agent;
 pub mod agent_orchestrator;
+pub mod knowledge_graph;
+pub mod export;
 
 // Re-export public API
-pub use concepts::{ConceptVector, SimilarityEngine, cosine_similarity};
-pub use embedding::EmbeddingGenerator;
+pub use concepts::{ConceptVector, SimilarityEngine, SimilarityError, SimilarityMetric, cosine_similarity, try_cosine_similarity};
+pub use embedding::{Embedder, EmbeddingGenerator, HashingEmbedder, TfidfEmbedder};
 pub use actions::ActionSystem;
 pub use state::StateUpdater;
 pub use module_agent::ModuleAgent;
 pub use agent_orchestrator::AgentOrchestrator;
+pub use knowledge_graph::KnowledgeGraph;
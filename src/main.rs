@@ -1,56 +1,157 @@
 // #FFD700 System Manifest (Enhanced with Module Agents)
-use starweave_mvp::concepts::{SimilarityEngine, cosine_similarity, ConceptVector};
-use starweave_mvp::embedding::EmbeddingGenerator;
+use starweave_mvp::concepts::{SimilarityEngine, ConceptPack, LearningConfig, CalibrationObjective, ThresholdJitter};
+use starweave_mvp::concepts::{ConceptDiscovery, DiscoveryConfig, SimilarityError};
+use starweave_mvp::concepts::calibration::load_examples;
+use starweave_mvp::embedding::{CachedEmbedder, Embedder, EmbeddingGenerator, HashingEmbedder, TfidfEmbedder};
 use starweave_mvp::actions::ActionSystem;
 use starweave_mvp::state::StateUpdater;
+use starweave_mvp::export::{ConceptGraph, ExportOptions};
 use starweave_mvp::module_agent::ModuleAgent;
 use ndarray::Array1;
+use anyhow::{bail, Result};
 use std::io;
+use std::sync::Arc;
+
+// Unmatched inputs collected between automatic clustering runs
+const DISCOVERY_INTERVAL: usize = 10;
+
+// Selects the embedding backend from the STARWEAVE_EMBEDDER environment variable
+fn build_embedder() -> Result<Box<dyn Embedder>> {
+    let backend = std::env::var("STARWEAVE_EMBEDDER").unwrap_or_else(|_| "mock".to_string());
+    match backend.as_str() {
+        "mock" => Ok(Box::new(EmbeddingGenerator::new()?)),
+        "hashing" => {
+            let dimension = std::env::var("STARWEAVE_EMBED_DIM")
+                .ok()
+                .and_then(|d| d.parse().ok())
+                .unwrap_or(256);
+            Ok(Box::new(HashingEmbedder::new(dimension)?))
+        }
+        "tfidf" => {
+            let model = std::env::var("STARWEAVE_TFIDF_MODEL")
+                .map_err(|_| anyhow::anyhow!("STARWEAVE_TFIDF_MODEL is not set"))?;
+            Ok(Box::new(TfidfEmbedder::load(model)?))
+        }
+        #[cfg(feature = "onnx")]
+        "onnx" => {
+            // Model and tokenizer must already exist locally (e.g. exported all-MiniLM-L6-v2)
+            let model = std::env::var("STARWEAVE_ONNX_MODEL")
+                .map_err(|_| anyhow::anyhow!("STARWEAVE_ONNX_MODEL is not set"))?;
+            let tokenizer = std::env::var("STARWEAVE_ONNX_TOKENIZER")
+                .map_err(|_| anyhow::anyhow!("STARWEAVE_ONNX_TOKENIZER is not set"))?;
+            Ok(Box::new(starweave_mvp::embedding::OnnxEmbedder::new(model, tokenizer)?))
+        }
+        other => bail!("unknown embedding backend '{other}'"),
+    }
+}
 
 fn main() {
     println!("🌟 STARWEAVE Vector Agent Initializing (Modular AI PoC)");
 
     // Initialize core components
-    let mut engine = SimilarityEngine::new();
-    let embedder = EmbeddingGenerator::new().unwrap();
+    let embedder = match build_embedder() {
+        Ok(embedder) => embedder,
+        Err(e) => {
+            println!("⚠️ Embedder setup failed: {e}. Falling back to mock embeddings.");
+            Box::new(EmbeddingGenerator::new().unwrap())
+        }
+    };
+
+    // Cache embeddings in memory, and on disk when STARWEAVE_EMBED_CACHE names a file
+    let mut cache = CachedEmbedder::new(embedder, 1024);
+    if let Ok(path) = std::env::var("STARWEAVE_EMBED_CACHE") {
+        if let Err(e) = cache.set_disk_store(&path) {
+            println!("⚠️ Embedding cache file unavailable: {e}. Caching in memory only.");
+        }
+    }
+    let embedder = Arc::new(cache);
+
+    // Load concepts from a JSON/TOML pack named by STARWEAVE_CONCEPTS, or use the built-in set
+    let mut engine = match std::env::var("STARWEAVE_CONCEPTS") {
+        Ok(path) => match ConceptPack::load(&path)
+            .and_then(|pack| SimilarityEngine::from_pack(&pack, Some(embedder.as_ref())))
+        {
+            Ok(engine) => engine,
+            Err(e) => {
+                println!("⚠️ Concept pack error: {e}. Using built-in concepts.");
+                SimilarityEngine::new()
+            }
+        },
+        Err(_) => SimilarityEngine::new(),
+    };
+    // STARWEAVE_METRIC picks the default similarity metric (cosine, dot, neg_euclidean, angular, mahalanobis)
+    // Hand-set thresholds (the built-in concepts, pack entries) are in the current units,
+    // so those concepts keep the current metric; suggested thresholds follow the new one
+    if let Ok(name) = std::env::var("STARWEAVE_METRIC") {
+        match name.parse() {
+            Ok(metric) => {
+                let current = engine.metric();
+                let hand_set: Vec<_> = engine.concepts().iter()
+                    .filter(|c| c.metric.is_none() && !c.auto_threshold)
+                    .map(|c| c.id)
+                    .collect();
+                for id in hand_set {
+                    if let Err(e) = engine.edit_concept(id, |concept| concept.metric = Some(current)) {
+                        println!("⚠️ {e}");
+                    }
+                }
+                engine.set_metric(metric); // Attaching the embedder below re-derives the rest
+            }
+            Err(e) => println!("⚠️ {e}. Using {}.", engine.metric()),
+        }
+    }
+    // STARWEAVE_LEARNING=<rate> lets /confirm move concepts towards confirmed inputs
+    if let Some(rate) = std::env::var("STARWEAVE_LEARNING").ok().and_then(|r| r.parse().ok()) {
+        if let Err(e) = engine.set_learning(Some(LearningConfig { learning_rate: rate, ..LearningConfig::default() })) {
+            println!("⚠️ STARWEAVE_LEARNING: {e}. Learning stays off.");
+        }
+    }
+    // STARWEAVE_JITTER=<amplitude> gives concepts without their own jitter a "threshold ± variation";
+    // STARWEAVE_SEED makes the variation reproducible
+    if let Some(amplitude) = std::env::var("STARWEAVE_JITTER").ok().and_then(|a| a.parse().ok()) {
+        let ids: Vec<_> = engine.concepts().iter().filter(|c| c.jitter.is_none()).map(|c| c.id).collect();
+        for id in ids {
+            if let Err(e) = engine.edit_concept(id, |concept| concept.jitter = Some(ThresholdJitter { amplitude })) {
+                println!("⚠️ {e}");
+            }
+        }
+    }
+    if let Some(seed) = std::env::var("STARWEAVE_SEED").ok().and_then(|s| s.parse().ok()) {
+        engine = engine.with_jitter_seed(seed);
+    }
     let mut action_system = ActionSystem::new();
     let mut state_updater = StateUpdater::new();
 
-    // Create specialized modules using concept names
-    let curiosity_concepts = engine.concepts.iter()
-        .filter(|c| c.name == "Curiosity")
-        .cloned()
-        .collect();
-    let curiosity_module = ModuleAgent::new("Curiosity", curiosity_concepts);
-
-    let aesthetics_concepts = engine.concepts.iter()
-        .filter(|c| c.name == "Aesthetics")
-        .cloned()
-        .collect();
-    let aesthetics_module = ModuleAgent::new("Aesthetics", aesthetics_concepts);
-
-    let verification_concepts = engine.concepts.iter()
-        .filter(|c| c.name == "Verification")
-        .cloned()
-        .collect();
-    let verification_module = ModuleAgent::new("Verification", verification_concepts);
-
-    // Register all modules with orchestrator
-    action_system.orchestrator.register_module(curiosity_module);
-    action_system.orchestrator.register_module(aesthetics_module);
-    action_system.orchestrator.register_module(verification_module);
-
-    println!("✅ {} concept vectors loaded", engine.concepts.len());
+    // Create one specialized module per concept and register it with the orchestrator
+    // Modules read the engine's concepts through a shared registry, so they never go stale
+    let registry = engine.shared_registry();
+    for concept in engine.concepts() {
+        let module = ModuleAgent::from_registry(&concept.name, registry.clone(), vec![concept.id]);
+        action_system.orchestrator.register_module(module);
+    }
+
+    println!("✅ {} concept vectors loaded", engine.concepts().len());
     println!("🚀 {} specialized modules registered", action_system.orchestrator.modules.len());
-    println!("   - Curiosity\n   - Aesthetics\n   - Verification");
+    for concept in engine.concepts() {
+        println!("   - {}", concept.name);
+    }
     println!("🔮 Co-creation propensity: {:.1}%", action_system.orchestrator.propensity_to_co_create * 100.0);
     println!("💡 Proactive prompts available: {}", action_system.orchestrator.proactive_prompts.len());
+    println!("🧬 Embedding model: {} ({} dims, {} similarity)", embedder.model_id(), embedder.dimension(), engine.metric());
+    match engine.set_embedder_with_projection(Box::new(embedder.clone()), 0) {
+        Ok(true) => println!("   ↳ Projected to {} dims to match stored concepts", engine.dimension().unwrap_or(0)),
+        Ok(false) => {}
+        Err(e) => println!("⚠️ Embedder could not be attached: {e}"),
+    }
     println!("🤝 Co-creation mode: {}\n", if action_system.co_creation_mode { "ENABLED" } else { "DISABLED" });
 
-    let mut interaction_count = 0;
+    let mut interaction_count: u32 = 0;
+    let mut last_match: Option<(String, Array1<f32>)> = None; // Candidate for /confirm
+    let mut discovery = ConceptDiscovery::new(DiscoveryConfig::default());
+    let mut unmatched_inputs: usize = 0; // Counts every recorded input, even once the buffer is full
 
     loop {
-        println!("Enter a concept to analyze (or type command: /co-create, /exit):");
+        println!("Enter a concept to analyze (or type command: /co-create, /confirm, /reset <concept>,\n  /calibrate <examples.jsonl|tsv> [precision=<p>], /graph <file.json>,\n  /export <file.dot|graphml>, /discover, /accept <id> <name>, /reject <id>,\n  /merge <into> + <absorbed>, /split <concept> -> <a>, <b>, /retire <concept>, /restore <concept>,\n  /veto <concept> unless <veto concept>, /veto <concept> not <text>, /exit):");
         let mut input = String::new();
         io::stdin().read_line(&mut input).unwrap();
         let input = input.trim();
@@ -73,19 +174,239 @@ fn main() {
             continue;
         }
 
+        // Export what the session has learned about concepts, inputs and collaborations
+        if let Some(path) = input.strip_prefix("/graph ") {
+            let graph = &action_system.orchestrator.knowledge_graph;
+            match graph.save(path.trim()) {
+                Ok(()) => println!("\n🕸️ Knowledge graph ({} nodes, {} edges) written to {}",
+                                   graph.node_count(), graph.edge_count(), path.trim()),
+                Err(e) => println!("\n⚠️ {e}"),
+            }
+            continue;
+        }
+
+        // Render concepts, modules and co-creation links for Graphviz or GraphML tools
+        if let Some(path) = input.strip_prefix("/export ") {
+            let graph = ConceptGraph::build(&engine, &action_system.orchestrator, ExportOptions::default());
+            match graph.write(path.trim()) {
+                Ok(()) => println!("\n🗺️ {} concepts, {} similarity links written to {}",
+                                   graph.concepts.len(), graph.similarities.len(), path.trim()),
+                Err(e) => println!("\n⚠️ {e}"),
+            }
+            continue;
+        }
+
+        // Cluster the inputs nothing matched into proposed concepts
+        if input == "/discover" {
+            let buffered = discovery.buffered();
+            let proposals = discovery.discover();
+            println!("\n🔭 {} proposals from {buffered} unmatched inputs", proposals.len());
+            for proposal in proposals {
+                println!("   {proposal}");
+            }
+            continue;
+        }
+
+        if let Some(args) = input.strip_prefix("/accept ") {
+            let (id, name) = args.trim().split_once(' ').unwrap_or((args.trim(), ""));
+            let (Ok(id), name) = (id.trim_start_matches('#').parse(), name.trim()) else {
+                println!("\n⚠️ Usage: /accept <id> <name>");
+                continue;
+            };
+            if name.is_empty() {
+                println!("\n⚠️ Give the new concept a name: /accept {id} <name>");
+                continue;
+            }
+            match discovery.accept(id, name, &mut engine) {
+                Ok(concept) => {
+                    let module = ModuleAgent::from_registry(&concept.name, engine.shared_registry(), vec![concept.id]);
+                    action_system.orchestrator.register_module(module);
+                    println!("\n🌱 New concept '{}' from {} inputs (threshold {:.2})",
+                             concept.name, concept.seed_texts.len(), concept.threshold);
+                }
+                Err(e) => println!("\n⚠️ {e}"),
+            }
+            continue;
+        }
+
+        if let Some(id) = input.strip_prefix("/reject ") {
+            match id.trim().trim_start_matches('#').parse() {
+                Ok(id) if discovery.reject(id) => println!("\n🗑️ Proposal #{id} rejected"),
+                _ => println!("\n⚠️ No pending proposal '{}'", id.trim()),
+            }
+            continue;
+        }
+
+        // Concept lifecycle: modules follow these changes through the shared concept registry
+        if let Some(args) = input.strip_prefix("/merge ") {
+            let Some((into, absorbed)) = args.split_once('+') else {
+                println!("\n⚠️ Usage: /merge <into> + <absorbed>");
+                continue;
+            };
+            let result = match (engine.id_of(into.trim()), engine.id_of(absorbed.trim())) {
+                (Some(into), Some(absorbed)) => engine.merge_concepts(into, absorbed).map(|merged| {
+                    format!("'{}' now has {} prototypes", merged.name, merged.prototype_vectors().len())
+                }),
+                _ => Err(SimilarityError::UnknownConcept { name: format!("{} / {}", into.trim(), absorbed.trim()) }),
+            };
+            match result {
+                Ok(message) => println!("\n🔗 Merged: {message}"),
+                Err(e) => println!("\n⚠️ {e}"),
+            }
+            continue;
+        }
+
+        if let Some(args) = input.strip_prefix("/split ") {
+            let Some((name, parts)) = args.split_once("->") else {
+                println!("\n⚠️ Usage: /split <concept> -> <a>, <b>");
+                continue;
+            };
+            let parts: Vec<&str> = parts.split(',').map(str::trim).filter(|p| !p.is_empty()).collect();
+            let result = engine.id_of(name.trim())
+                .ok_or_else(|| SimilarityError::UnknownConcept { name: name.trim().to_string() })
+                .and_then(|id| engine.split_concept(id, &parts));
+            match result {
+                Ok(_) => println!("\n✂️ '{}' split into {}", name.trim(), parts.join(", ")),
+                Err(e) => println!("\n⚠️ {e}"),
+            }
+            continue;
+        }
+
+        if let Some(name) = input.strip_prefix("/retire ") {
+            let result = engine.id_of(name.trim())
+                .ok_or_else(|| SimilarityError::UnknownConcept { name: name.trim().to_string() })
+                .and_then(|id| engine.retire_concept(id));
+            match result {
+                Ok(()) => println!("\n💤 '{}' retired; /restore brings it back", name.trim()),
+                Err(e) => println!("\n⚠️ {e}"),
+            }
+            continue;
+        }
+
+        if let Some(name) = input.strip_prefix("/restore ") {
+            let result = engine.retired_concepts().iter().find(|c| c.name == name.trim()).map(|c| c.id)
+                .ok_or_else(|| SimilarityError::UnknownConcept { name: name.trim().to_string() })
+                .and_then(|id| engine.restore_concept(id));
+            match result {
+                Ok(()) => println!("\n🌅 '{}' restored", name.trim()),
+                Err(e) => println!("\n⚠️ {e}"),
+            }
+            continue;
+        }
+
+        // Exclusion rules: "/veto Aesthetics unless Code formatting" names a veto concept,
+        // "/veto Aesthetics not <text>" adds the text's embedding as a negative exemplar
+        if let Some(args) = input.strip_prefix("/veto ") {
+            let (name, rule) = match (args.split_once(" unless "), args.split_once(" not ")) {
+                (Some((name, veto)), _) => (name.trim(), Ok(veto.trim().to_string())),
+                (None, Some((name, text))) => (name.trim(), Err(text.trim())),
+                (None, None) => {
+                    println!("\n⚠️ Usage: /veto <concept> unless <veto concept>  |  /veto <concept> not <text>");
+                    continue;
+                }
+            };
+            let (result, description) = match rule {
+                Ok(veto) => {
+                    let mut exclusion = engine.concept(name).and_then(|c| c.exclusion.clone()).unwrap_or_default();
+                    let description = format!("whenever '{veto}' fits");
+                    exclusion.vetoes.push(veto);
+                    (engine.set_exclusion(name, Some(exclusion)), description)
+                }
+                Err(text) => (engine.add_veto_text(name, text), format!("for inputs like '{text}'")),
+            };
+            match result {
+                Ok(()) => println!("\n🚫 '{name}' is vetoed {description}"),
+                Err(e) => println!("\n⚠️ {e}"),
+            }
+            continue;
+        }
+
+        // Teach the last matched concept that it was right
+        if input == "/confirm" {
+            match last_match.take() {
+                Some((name, embedding)) => match engine.confirm_match(&name, &embedding) {
+                    Ok(Some(drift)) => println!("\n📈 '{name}' learned from the input (drift {drift:.3})"),
+                    Ok(None) => println!("\nℹ️ Learning is disabled; set STARWEAVE_LEARNING to a rate to enable it."),
+                    Err(e @ SimilarityError::InvalidOperation { .. }) => println!("\nℹ️ {e}"),
+                    Err(e) => println!("\n⚠️ Could not learn: {e}"),
+                },
+                None => println!("\nℹ️ Nothing to confirm yet."),
+            }
+            continue;
+        }
+
+        if let Some(name) = input.strip_prefix("/reset ") {
+            match engine.reset_concept(name.trim()) {
+                Ok(()) => println!("\n↩️ '{}' restored to its original vector", name.trim()),
+                Err(e) => println!("\n⚠️ {e}"),
+            }
+            continue;
+        }
+
+        // Tune thresholds on a labelled dataset and write them back to the loaded pack
+        if let Some(args) = input.strip_prefix("/calibrate ") {
+            let mut args = args.split_whitespace();
+            let dataset = args.next().unwrap_or_default();
+            let objective = match args.next().and_then(|a| a.strip_prefix("precision=")) {
+                Some(target) => match target.parse() {
+                    Ok(value) if (0.0..=1.0).contains(&value) => CalibrationObjective::TargetPrecision(value),
+                    _ => {
+                        println!("\n⚠️ '{target}' is not a precision between 0 and 1");
+                        continue;
+                    }
+                },
+                None => CalibrationObjective::MaxF1,
+            };
+            match load_examples(dataset).and_then(|examples| engine.calibrate(&examples, objective)) {
+                Ok(report) => {
+                    println!("\n🎯 {report}");
+                    if let Ok(path) = std::env::var("STARWEAVE_CONCEPTS") {
+                        let written = ConceptPack::load(&path).and_then(|mut pack| {
+                            let updated = report.apply_to_pack(&mut pack);
+                            pack.save(&path).map(|_| updated)
+                        });
+                        match written {
+                            Ok(updated) => println!("💾 {updated} thresholds written to {path}"),
+                            Err(e) => println!("⚠️ Could not update {path}: {e}"),
+                        }
+                    }
+                }
+                Err(e) => println!("\n⚠️ Calibration failed: {e}"),
+            }
+            continue;
+        }
+
         // Generate embedding
-        let embedding = match embedder.embed(input) {
+        let embedding = match engine.embed(input) {
             Ok(emb) => emb,
             Err(e) => {
                 println!("\n⚠️ Embedding error: {e}. Using default vector.");
-                Array1::zeros(384) // Use a default vector if embedding fails
+                Array1::zeros(engine.dimension().unwrap_or(3)) // Use a default vector if embedding fails
             }
         };
 
-        // Detect best matching concept
-        if let Some(concept) = engine.find_best_match(&embedding) {
-            println!("\n✨ Best match: {}!", concept.name);
-            println!("   Similarity: {:.2}", cosine_similarity(&concept.vector, &embedding));
+        // Rank concepts and keep the explanation for the audit trail; the action below
+        // follows the same explanation so both see one jitter draw
+        let explanation = match engine.explain(&embedding, 3) {
+            Ok(explanation) => {
+                println!("\n🧾 Match explanation:\n{explanation}");
+                action_system.record_explanation(input, &explanation);
+                Some(explanation)
+            }
+            Err(e) => {
+                println!("\n⚠️ Similarity error: {e}");
+                None
+            }
+        };
+        // With a taxonomy, refine from the roots down instead of taking the flat winner
+        let best_match = explanation.and_then(|ex| if engine.has_taxonomy() {
+            ex.refined.map(|m| (m.concept, m.path, m.similarity))
+        } else {
+            ex.winner.map(|w| (w.concept, w.path, w.similarity))
+        });
+        if let Some((concept, path, similarity)) = best_match {
+            println!("\n✨ Best match: {path}!");
+            println!("   Similarity: {similarity:.2}");
             println!("   Curiosity score: {:.2}", concept.curiosity_score);
             println!("   State before update: [{:.3}, {:.3}]",
                 concept.stochastic_state[0], concept.stochastic_state[1]);
@@ -100,18 +421,35 @@ fn main() {
             println!("   Updated curiosity:   {:.3}", evolved_concept.curiosity_score);
 
             // Trigger action
-            let response = action_system.trigger_action(&evolved_concept, input);
+            let response = action_system.trigger_path_action(&path, &evolved_concept, input);
             println!("\n💫 System action:\n{response}\n");
 
-            // Update original concept in engine
+            // Update original concept in engine; the evolved state feeds threshold jitter
             engine.update_concept_after_interaction(&concept.name);
+            let stored = engine.edit_concept(concept.id, |stored| {
+                stored.stochastic_state = evolved_concept.stochastic_state;
+                stored.curiosity_score = evolved_concept.curiosity_score;
+            }).map(|_| ());
+            if let Err(e) = stored.and_then(|()| engine.record_match(concept.id, &embedding)) {
+                println!("⚠️ {e}");
+            }
+            last_match = Some((concept.name.clone(), embedding.clone()));
         } else {
+            last_match = None;
             println!("\n🔍 No strong match found. Responding with default action.");
             println!("💬 I have processed your input about '{input}'");
-            action_system.trigger_action(
-                &ConceptVector::default(),
-                input
-            );
+            action_system.trigger_default_action(input);
+
+            // Keep the input so recurring unmatched topics can become concepts
+            discovery.record(input, &embedding);
+            unmatched_inputs += 1;
+            if unmatched_inputs % DISCOVERY_INTERVAL == 0 {
+                let known: Vec<u64> = discovery.proposals().iter().map(|p| p.id).collect();
+                let fresh = discovery.discover().iter().filter(|p| !known.contains(&p.id)).count();
+                if fresh > 0 {
+                    println!("🔭 {fresh} new concept proposals; type /discover to review them");
+                }
+            }
         }
 
         // Trigger self-reflection periodically
@@ -141,4 +479,22 @@ fn main() {
     for (name, module) in &action_system.orchestrator.modules {
         println!("   Module '{name}': {} co-creations", module.co_creation_count);
     }
+
+    let graph = &action_system.orchestrator.knowledge_graph;
+    println!("\n🕸️ Knowledge graph: {} nodes, {} edges", graph.node_count(), graph.edge_count());
+    for concept in engine.concepts() {
+        let related = graph.related_concepts(&concept.name, 3);
+        if !related.is_empty() {
+            let names: Vec<&str> = related.iter().map(|(name, _)| name.as_str()).collect();
+            println!("   {} ↔ {}", concept.name, names.join(", "));
+        }
+    }
+
+    for stats in engine.learning_stats() {
+        println!("📈 '{}' drifted {:.3} over {} confirmations", stats.concept, stats.drift, stats.updates);
+    }
+
+    let stats = embedder.stats();
+    println!("\n🧬 Embedding Cache: {} hits, {} disk hits, {} misses ({:.0}% hit rate)",
+             stats.hits, stats.disk_hits, stats.misses, stats.hit_rate() * 100.0);
 }
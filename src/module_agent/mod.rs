@@ -1,34 +1,110 @@
 // #ADD8E6 Module Agent Definition
-use crate::concepts::{ConceptVector, SimilarityEngine};
+use crate::concepts::{ConceptId, ConceptRegistry, ConceptVector, SimilarityEngine};
+use crate::embedding::Embedder;
 use ndarray::Array1;
+use anyhow::Result;
 
 pub struct ModuleAgent {
     pub name: String,
-    pub concepts: Vec<ConceptVector>,
-    pub local_engine: SimilarityEngine,
+    pub concept_ids: Vec<ConceptId>,    // Concepts this module covers, looked up in the registry
+    pub local_engine: SimilarityEngine, // View over `concept_ids`, rebuilt when the registry changes
     pub co_creation_count: u32,
+    registry: ConceptRegistry,
+    generation: u64,                    // Registry generation the view was built from
 }
 
 impl ModuleAgent {
+    // A standalone module that owns its concepts
     pub fn new(name: &str, concepts: Vec<ConceptVector>) -> Self {
-        let local_engine = SimilarityEngine { concepts: concepts.clone() };
+        let mut local_engine = SimilarityEngine::from_concepts(concepts);
+        let registry = local_engine.shared_registry();
+        let concept_ids = local_engine.concepts().iter().map(|c| c.id).collect();
         ModuleAgent {
             name: name.to_string(),
-            concepts,
+            concept_ids,
+            generation: registry.generation(),
             local_engine,
             co_creation_count: 0,
+            registry,
         }
     }
 
+    // A module over concepts owned by another engine (see `SimilarityEngine::shared_registry`).
+    // Curiosity, timestamps, learned vectors, merges and splits made there show up here.
+    pub fn from_registry(name: &str, registry: ConceptRegistry, concept_ids: Vec<ConceptId>) -> Self {
+        let mut module = ModuleAgent {
+            name: name.to_string(),
+            concept_ids,
+            generation: registry.generation(),
+            local_engine: SimilarityEngine::from_concepts(Vec::new()),
+            co_creation_count: 0,
+            registry,
+        };
+        module.rebuild();
+        module
+    }
+
+    // Creates a module whose local engine embeds raw text with the given backend
+    pub fn with_embedder(name: &str, concepts: Vec<ConceptVector>, embedder: Box<dyn Embedder>) -> Result<Self> {
+        let mut module = Self::new(name, concepts);
+        module.local_engine.set_embedder(embedder)?;
+        Ok(module)
+    }
+
+    // Current state of this module's concepts
+    pub fn concepts(&self) -> Vec<ConceptVector> {
+        self.registry.concepts(&self.registry.resolve(&self.concept_ids))
+    }
+
+    // Rebuilds the local engine if the registry changed; returns whether it did
+    pub fn sync(&mut self) -> bool {
+        let generation = self.registry.generation();
+        if generation == self.generation {
+            return false;
+        }
+        self.concept_ids = self.registry.resolve(&self.concept_ids);
+        self.rebuild();
+        self.generation = generation;
+        true
+    }
+
+    // Loads this module's concepts into the local engine, along with the veto concepts
+    // their exclusion rules name, so "unless…" rules fire here as in the owning engine
+    fn rebuild(&mut self) {
+        let concepts = self.registry.concepts(&self.concept_ids);
+        let mut vetoes: Vec<&str> = concepts.iter()
+            .flat_map(|c| c.exclusion.iter().flat_map(|e| &e.vetoes))
+            .map(String::as_str)
+            .filter(|v| !concepts.iter().any(|c| c.name == *v))
+            .collect();
+        vetoes.sort_unstable();
+        vetoes.dedup();
+        let sources = vetoes.into_iter().filter_map(|v| self.registry.get_by_name(v)).collect();
+        self.local_engine.replace_concepts(concepts);
+        self.local_engine.set_veto_sources(sources);
+    }
+
     // Process input within this module's context
     pub fn process_input(&mut self, input_vec: &Array1<f32>) -> Option<ConceptVector> {
-        self.local_engine.find_best_match(input_vec)
+        self.sync();
+        self.local_engine.find_best_match(input_vec).map(|c| self.current(c))
+    }
+
+    // Process raw text using the local engine's embedder
+    pub fn process_text(&mut self, input: &str) -> Result<Option<ConceptVector>> {
+        self.sync();
+        Ok(self.local_engine.find_best_match_text(input)?.map(|c| self.current(c)))
+    }
+
+    // The registry's copy of a matched concept, with bookkeeping the view wasn't rebuilt for
+    fn current(&self, concept: ConceptVector) -> ConceptVector {
+        self.registry.get(concept.id).unwrap_or(concept)
     }
 
     // Suggest a concept to another module based on implicit connections
-    pub fn suggest_concept(&self, _other: &str) -> Option<&ConceptVector> {
+    pub fn suggest_concept(&self, _other: &str) -> Option<ConceptVector> {
         // Simple implicit suggestion: find the concept with highest curiosity
-        self.concepts.iter()
+        self.concepts().into_iter()
             .max_by(|a, b| a.curiosity_score.partial_cmp(&b.curiosity_score).unwrap())
     }
 
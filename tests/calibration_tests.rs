@@ -0,0 +1,66 @@
+// #FF69B4 Threshold Calibration Tests
+use starweave_mvp::concepts::calibration::load_examples;
+use starweave_mvp::concepts::{CalibrationObjective, ConceptVector, SimilarityEngine};
+use ndarray::{Array1, Array2};
+
+fn engine() -> SimilarityEngine {
+    let concept = |name: &str, vector: Vec<f32>| ConceptVector {
+        name: name.to_string(),
+        vector: Array1::from_vec(vector),
+        threshold: 0.99, // Far too strict: almost nothing matches
+        ..ConceptVector::default()
+    };
+    SimilarityEngine::from_concepts(vec![concept("Science", vec![1.0, 0.0]), concept("Art", vec![0.0, 1.0])])
+}
+
+#[test]
+fn test_calibration_improves_thresholds_and_updates_the_pack() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("labels.tsv");
+    std::fs::write(&path, "measure the orbit\tScience\npaint the sky\tArt\nhello there\tnone\n").unwrap();
+    let examples = load_examples(&path).unwrap();
+    assert_eq!(examples[1].expected.as_deref(), Some("Art"));
+    assert_eq!(examples[2].expected, None);
+
+    let jsonl = dir.path().join("labels.jsonl");
+    std::fs::write(&jsonl, "{\"text\": \"weigh the evidence\", \"concept\": \"Science\"}\n{\"text\": \"small talk\"}\n").unwrap();
+    assert_eq!(load_examples(&jsonl).unwrap()[1].expected, None);
+
+    let inputs = Array2::from_shape_vec((6, 2), vec![
+        0.95, 0.30, // Science
+        0.90, 0.40, // Science
+        0.30, 0.95, // Art
+        0.45, 0.90, // Art
+        0.70, 0.70, // Ambiguous: should match nothing
+        0.65, 0.75, // Ambiguous: should match nothing
+    ]).unwrap();
+    let labels = [Some("Science"), Some("Science"), Some("Art"), Some("Art"), None, None];
+
+    let mut engine = engine();
+    let registry = engine.shared_registry();
+    let report = engine.calibrate_vectors(&inputs, &labels, CalibrationObjective::MaxF1).unwrap();
+    // Shared views see the new thresholds
+    let science = engine.concepts()[0].clone();
+    assert_eq!(registry.get(science.id).unwrap().threshold, science.threshold);
+    assert!(report.accuracy_after > report.accuracy_before);
+    assert_eq!(report.accuracy_after, 1.0);
+    for concept in &report.concepts {
+        assert_eq!(concept.after_metrics.f1, 1.0);
+        assert!(concept.after < concept.before);
+    }
+    assert!(report.to_string().contains("Best-match accuracy"));
+
+    let mut pack = engine.to_pack();
+    pack.concepts.iter_mut().for_each(|d| d.threshold = None);
+    assert_eq!(report.apply_to_pack(&mut pack), 2);
+    assert_eq!(pack.concepts[0].threshold, Some(engine.concepts()[0].threshold));
+
+    // A precision target of 1.0 is reachable here, and unknown labels are rejected
+    let mut strict = self::engine();
+    let report = strict.calibrate_vectors(&inputs, &labels, CalibrationObjective::TargetPrecision(1.0)).unwrap();
+    assert!(report.concepts.iter().all(|c| c.met_target && c.after_metrics.precision == 1.0));
+    assert!(strict.calibrate_vectors(&inputs.slice(ndarray::s![..1, ..]).to_owned(), &[Some("Music")], CalibrationObjective::MaxF1).is_err());
+    for target in [2.0, -0.1, f32::NAN] {
+        assert!(strict.calibrate_vectors(&inputs, &labels, CalibrationObjective::TargetPrecision(target)).is_err());
+    }
+}
@@ -0,0 +1,66 @@
+// #FF69B4 Concept Discovery Tests
+use ndarray::{array, Array1};
+use starweave_mvp::concepts::{ClusteringMethod, ConceptDiscovery, DiscoveryConfig, SimilarityEngine};
+
+fn around(direction: [f32; 3], wobble: f32) -> Array1<f32> {
+    array![direction[0] + wobble, direction[1] - wobble, direction[2] + wobble / 2.0]
+}
+
+fn fill(discovery: &mut ConceptDiscovery) {
+    for (i, wobble) in [0.0, 0.05, -0.05, 0.1].into_iter().enumerate() {
+        discovery.record(&format!("recipe {i}"), &around([0.0, 0.0, 1.0], wobble));
+        discovery.record(&format!("weather {i}"), &around([0.0, 1.0, 0.0], wobble));
+    }
+    discovery.record("lonely outlier", &array![-1.0, -1.0, 0.0]);
+    discovery.record("failed embedding", &array![0.0, 0.0, 0.0]); // Ignored
+}
+
+#[test]
+fn test_unmatched_inputs_cluster_into_proposals() {
+    for method in [ClusteringMethod::Agglomerative, ClusteringMethod::KMeans { k: 3, iterations: 20, seed: 7 }] {
+        let mut discovery = ConceptDiscovery::new(DiscoveryConfig { method, ..DiscoveryConfig::default() });
+        fill(&mut discovery);
+        assert_eq!(discovery.buffered(), 9);
+
+        let proposals = discovery.discover();
+        assert_eq!(proposals.len(), 2, "{method:?}"); // The outlier is too small to propose
+        assert!(proposals.iter().all(|p| p.size == 4 && p.cohesion > 0.9 && p.examples.len() == 3));
+        let recipes = proposals.iter().find(|p| p.examples[0].starts_with("recipe")).unwrap();
+        assert!(recipes.examples.iter().all(|e| e.starts_with("recipe")));
+        assert!(recipes.vector[2] > 0.9);
+        let recipes = recipes.id;
+
+        // Rediscovering keeps the ids of clusters that are still there, even as they grow
+        discovery.record("recipe 4", &around([0.0, 0.0, 1.0], 0.02));
+        let again = discovery.discover();
+        let grown = again.iter().find(|p| p.examples[0].starts_with("recipe")).unwrap();
+        assert_eq!((grown.id, grown.size), (recipes, 5), "{method:?}");
+    }
+}
+
+#[test]
+fn test_accepting_and_rejecting_proposals() {
+    let mut engine = SimilarityEngine::new();
+    let mut discovery = ConceptDiscovery::default();
+    fill(&mut discovery);
+    let ids: Vec<(u64, bool)> = discovery.discover().iter()
+        .map(|p| (p.id, p.examples[0].starts_with("recipe")))
+        .collect();
+    let (recipes, _) = ids.iter().find(|(_, recipe)| *recipe).copied().unwrap();
+    let (weather, _) = ids.iter().find(|(_, recipe)| !*recipe).copied().unwrap();
+
+    let concept = discovery.accept(recipes, "Cooking", &mut engine).unwrap();
+    assert_eq!(concept.seed_texts.len(), 4);
+    assert!(concept.threshold < 0.95);
+    assert_eq!(engine.find_best_match(&around([0.0, 0.0, 1.0], 0.02)).unwrap().name, "Cooking");
+    assert!(discovery.accept(recipes, "Again", &mut engine).is_err());
+
+    // Rejected clusters leave the buffer and are not proposed again
+    assert!(discovery.reject(weather));
+    assert!(!discovery.reject(weather));
+    assert_eq!(discovery.buffered(), 1);
+    for i in 0..4 {
+        discovery.record(&format!("forecast {i}"), &around([0.0, 1.0, 0.0], 0.01 * i as f32));
+    }
+    assert!(discovery.discover().is_empty());
+}
@@ -0,0 +1,216 @@
+// #9400D3 Embedding Backend Tests
+use starweave_mvp::concepts::SimilarityEngine;
+use starweave_mvp::embedding::{Embedder, EmbeddingGenerator};
+use starweave_mvp::module_agent::ModuleAgent;
+
+#[test]
+fn test_mock_embedder_through_trait_object() {
+    let embedder: Box<dyn Embedder> = Box::new(EmbeddingGenerator::new().unwrap());
+
+    let embedding = embedder.embed("stars").unwrap();
+    assert_eq!(embedding.len(), embedder.dimension());
+    assert_eq!(embedder.model_id(), EmbeddingGenerator::MODEL_ID);
+
+    // Default batch implementation stacks single embeddings row by row
+    let batch = embedder.embed_batch(&["stars", "a much longer sentence"]).unwrap();
+    assert_eq!(batch.dim(), (2, embedder.dimension()));
+    assert_eq!(batch.row(0), embedding);
+}
+
+#[test]
+fn test_engine_matches_text_with_attached_embedder() {
+    let engine = SimilarityEngine::new();
+    assert!(engine.find_best_match_text("stars").is_err());
+
+    let engine = engine.with_embedder(EmbeddingGenerator::new().unwrap()).unwrap();
+    let direct = engine.find_best_match(&EmbeddingGenerator::new().unwrap().embed("stars").unwrap());
+    let via_text = engine.find_best_match_text("stars").unwrap();
+    assert_eq!(direct.map(|c| c.name), via_text.map(|c| c.name));
+}
+
+#[test]
+fn test_module_agent_accepts_boxed_embedder() {
+    let engine = SimilarityEngine::new();
+    let mut module = ModuleAgent::with_embedder(
+        "All",
+        engine.concepts().to_vec(),
+        Box::new(EmbeddingGenerator::new().unwrap()),
+    ).unwrap();
+    assert!(module.process_text("stars").is_ok());
+}
+
+#[cfg(feature = "onnx")]
+#[test]
+fn test_onnx_embedder_requires_local_files() {
+    use starweave_mvp::embedding::OnnxEmbedder;
+
+    let result = OnnxEmbedder::new("missing/model.onnx", "missing/tokenizer.json");
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_hashing_embedder_depends_on_content() {
+    use starweave_mvp::concepts::cosine_similarity;
+    use starweave_mvp::embedding::{HashingConfig, HashingEmbedder};
+
+    let embedder = HashingEmbedder::new(256).unwrap();
+    let cat = embedder.embed("cat").unwrap();
+    let dog = embedder.embed("dog").unwrap();
+    assert_ne!(cat, dog);
+    assert_eq!(cat, embedder.embed("cat").unwrap());
+    assert!((cat.dot(&cat).sqrt() - 1.0).abs() < 1e-5);
+
+    let query = embedder.embed("please verify these facts").unwrap();
+    let related = embedder.embed("verify the facts").unwrap();
+    let unrelated = embedder.embed("paint a sunset").unwrap();
+    assert!(cosine_similarity(&query, &related) > cosine_similarity(&query, &unrelated));
+
+    // Weights change the vectors, so they change the model id too
+    let reweighted = HashingEmbedder::with_config(HashingConfig { dimension: 256, bigram_weight: 0.0, ..HashingConfig::default() }).unwrap();
+    assert_ne!(reweighted.model_id(), embedder.model_id());
+}
+
+#[test]
+fn test_tfidf_embedder_fits_directory_and_round_trips() {
+    use starweave_mvp::concepts::cosine_similarity;
+    use starweave_mvp::embedding::{Reduction, TfidfConfig, TfidfEmbedder};
+
+    let corpus = tempfile::tempdir().unwrap();
+    let documents = [
+        ("art.txt", "colour painting canvas brush beauty"),
+        ("design.md", "beautiful colour design visual painting"),
+        ("palette.txt", "visual design beauty colour palette"),
+        ("tests.txt", "unit testing verifies code assertions"),
+        ("facts.txt", "fact checking verifies claims sources"),
+        ("review.md", "testing code verifies claims assertions"),
+        ("ignored.rs", "fn main() {}"),
+    ];
+    for (name, text) in documents {
+        std::fs::write(corpus.path().join(name), text).unwrap();
+    }
+
+    let config = TfidfConfig {
+        reduction: Reduction::TruncatedSvd { dimension: 2, seed: 7 },
+        ..TfidfConfig::default()
+    };
+    let embedder = TfidfEmbedder::fit_dir(corpus.path(), &config).unwrap();
+    assert_eq!(embedder.dimension(), 2);
+    assert_eq!(embedder.model().document_count, 6);
+
+    let art = embedder.embed("colour painting").unwrap();
+    let design = embedder.embed("visual colour design").unwrap();
+    let testing = embedder.embed("unit testing assertions").unwrap();
+    assert!(cosine_similarity(&art, &design) > cosine_similarity(&art, &testing));
+
+    let path = corpus.path().join("model.json");
+    embedder.save(&path).unwrap();
+    let restored = TfidfEmbedder::load(&path).unwrap();
+    assert_eq!(restored.model_id(), embedder.model_id());
+    assert_eq!(restored.embed("colour painting").unwrap(), art);
+
+    // Same vocabulary, different weights: a different model as far as caches go
+    let mut reweighted = embedder.model().clone();
+    reweighted.idf[0] += 1.0;
+    assert_ne!(TfidfEmbedder::from_model(reweighted).model_id(), embedder.model_id());
+
+    // Corrupt files are rejected on load instead of panicking on the first embed
+    let mut out_of_range = embedder.model().clone();
+    *out_of_range.vocabulary.values_mut().next().unwrap() = out_of_range.idf.len();
+    TfidfEmbedder::from_model(out_of_range).save(&path).unwrap();
+    assert!(TfidfEmbedder::load(&path).is_err());
+    let mut short_projection = embedder.model().clone();
+    short_projection.projection = Some(ndarray::Array2::zeros((3, 2)));
+    TfidfEmbedder::from_model(short_projection).save(&path).unwrap();
+    assert!(TfidfEmbedder::load(&path).is_err());
+    let mut narrow_projection = embedder.model().clone();
+    narrow_projection.projection = Some(ndarray::Array2::zeros((narrow_projection.idf.len(), 1)));
+    TfidfEmbedder::from_model(narrow_projection).save(&path).unwrap();
+    assert!(TfidfEmbedder::load(&path).err().is_some_and(|e| e.to_string().contains("reduction.dimension")));
+    let mut shared_index = embedder.model().clone();
+    let mut indices = shared_index.vocabulary.values_mut();
+    let first = *indices.next().unwrap();
+    *indices.next().unwrap() = first;
+    TfidfEmbedder::from_model(shared_index).save(&path).unwrap();
+    assert!(TfidfEmbedder::load(&path).err().is_some_and(|e| e.to_string().contains("reuses index")));
+
+    // Six documents can't yield sixteen components; the rest are zero rather than dropped
+    let wide = TfidfConfig { reduction: Reduction::TruncatedSvd { dimension: 16, seed: 7 }, ..TfidfConfig::default() };
+    let wide = TfidfEmbedder::fit_dir(corpus.path(), &wide).unwrap();
+    assert_eq!(wide.dimension(), 16);
+    assert_eq!(wide.embed("colour painting").unwrap().len(), 16);
+}
+
+#[test]
+fn test_cached_embedder_counts_hits_and_persists_to_disk() {
+    use starweave_mvp::embedding::{CachedEmbedder, HashingEmbedder};
+    use std::io::Write;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cache.jsonl");
+
+    let cache = CachedEmbedder::new(HashingEmbedder::new(32).unwrap(), 2)
+        .with_disk_store(&path)
+        .unwrap();
+    let first = cache.embed("verify  the facts ").unwrap();
+    assert_eq!(cache.embed("verify the facts").unwrap(), first);
+    cache.embed("paint a sunset").unwrap();
+    cache.embed("explore the stars").unwrap();
+
+    let stats = cache.stats();
+    assert_eq!((stats.hits, stats.misses, stats.evictions), (1, 3, 1));
+    assert_eq!((stats.memory_entries, stats.disk_entries), (2, 3));
+
+    // A fresh cache over the same file serves earlier embeddings from disk
+    let reopened = CachedEmbedder::new(HashingEmbedder::new(32).unwrap(), 2)
+        .with_disk_store(&path)
+        .unwrap();
+    let batch = reopened.embed_batch(&["verify the facts", "something new"]).unwrap();
+    assert_eq!(batch.row(0), first);
+    let stats = reopened.stats();
+    assert_eq!((stats.disk_hits, stats.misses), (1, 1));
+
+    // Entries from a different model in the same file are ignored
+    let other = CachedEmbedder::new(HashingEmbedder::new(16).unwrap(), 2)
+        .with_disk_store(&path)
+        .unwrap();
+    assert_eq!(other.stats().disk_entries, 0);
+
+    // So are entries whose vector doesn't fit the model, even under its id
+    let model = HashingEmbedder::new(32).unwrap().model_id().to_string();
+    let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+    writeln!(file, r#"{{"model":"{model}","text":"tampered","vector":[1.0]}}"#).unwrap();
+    let checked = CachedEmbedder::new(HashingEmbedder::new(32).unwrap(), 2)
+        .with_disk_store(&path)
+        .unwrap();
+    assert_eq!(checked.embed("tampered").unwrap().len(), 32);
+    assert_eq!((checked.stats().disk_hits, checked.stats().misses), (0, 1));
+
+    // A corrupt line found during lookup is a miss, not an error
+    let bytes = std::fs::read(&path).unwrap();
+    let start = String::from_utf8_lossy(&bytes).find("\"vector\"").unwrap();
+    let mut corrupted = bytes.clone();
+    corrupted[start] = b'#';
+    let cache = CachedEmbedder::new(HashingEmbedder::new(32).unwrap(), 2).with_disk_store(&path).unwrap();
+    std::fs::write(&path, corrupted).unwrap();
+    assert_eq!(cache.embed("verify the facts").unwrap(), first);
+    assert_eq!((cache.stats().disk_hits, cache.stats().misses), (0, 1));
+}
+
+#[test]
+fn test_cached_embedder_embeds_the_callers_text() {
+    use starweave_mvp::embedding::{CachedEmbedder, EmbeddingGenerator};
+
+    // The original generator depends on text length, spacing included
+    let generator = EmbeddingGenerator::new().unwrap();
+    let cache = CachedEmbedder::new(EmbeddingGenerator::new().unwrap(), 4);
+    assert_eq!(cache.embed("  spaced   out  ").unwrap(), generator.embed("  spaced   out  ").unwrap());
+    let batch = cache.embed_batch(&["tabs\there"]).unwrap();
+    assert_eq!(batch.row(0), generator.embed("tabs\there").unwrap());
+
+    // Texts sharing a key within one batch are embedded once
+    let before = cache.stats();
+    let batch = cache.embed_batch(&["a b", "a  b", "c"]).unwrap();
+    assert_eq!(batch.row(0), batch.row(1));
+    let after = cache.stats();
+    assert_eq!((after.hits - before.hits, after.misses - before.misses), (1, 2));
+}
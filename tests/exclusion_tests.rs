@@ -0,0 +1,152 @@
+// #FF69B4 Exclusion Rule Tests
+use starweave_mvp::concepts::{
+    ConceptPack, ConceptVector, Exclusion, SimilarityEngine, SimilarityError, VetoAction, VetoSource,
+};
+use starweave_mvp::actions::ActionSystem;
+use starweave_mvp::embedding::HashingEmbedder;
+use starweave_mvp::module_agent::ModuleAgent;
+use ndarray::{arr1, arr2, Array1};
+
+fn concept(name: &str, vector: [f32; 3], threshold: f32) -> ConceptVector {
+    ConceptVector { name: name.to_string(), vector: arr1(&vector), threshold, ..ConceptVector::default() }
+}
+
+#[test]
+fn test_veto_concept_suppresses_match_and_is_reported() {
+    // "Match Aesthetics unless the input is about code formatting"
+    let mut engine = SimilarityEngine::from_concepts(vec![
+        concept("Aesthetics", [0.0, 1.0, 0.0], 0.5),
+        concept("Code formatting", [0.6, 0.8, 0.0], 0.99),
+    ]);
+    engine.set_exclusion("Aesthetics", Some(Exclusion { vetoes: vec!["Code formatting".to_string()], ..Exclusion::default() })).unwrap();
+
+    let about_beauty = arr1(&[-0.3, 1.0, 0.0]);
+    assert_eq!(engine.find_best_match(&about_beauty).unwrap().name, "Aesthetics");
+
+    let about_formatting = arr1(&[0.6, 0.8, 0.0]);
+    assert_eq!(engine.find_best_match(&about_formatting).unwrap().name, "Code formatting");
+    let batch = engine.match_batch(&arr2(&[[-0.3, 1.0, 0.0], [0.6, 0.8, 0.0]])).unwrap();
+    assert_eq!(batch[0].unwrap().concept, 0);
+    assert_eq!(batch[1].unwrap().concept, 1);
+
+    let ranked = engine.top_k(&about_formatting, 2).unwrap();
+    let aesthetics = ranked.iter().find(|r| r.concept.name == "Aesthetics").unwrap();
+    let veto = aesthetics.veto.as_ref().expect("veto is reported");
+    assert_eq!(veto.source, VetoSource::Concept("Code formatting".to_string()));
+    assert!(veto.suppresses());
+    assert!(!aesthetics.passed_threshold && aesthetics.similarity > aesthetics.threshold);
+    assert!(aesthetics.to_string().contains("vetoed by 'Code formatting'"));
+    assert!(engine.explain(&about_formatting, 2).unwrap().summary().contains("vetoed"));
+}
+
+#[test]
+fn test_negative_exemplar_penalizes_similarity() {
+    let mut engine = SimilarityEngine::from_concepts(vec![concept("Stars", [1.0, 0.0, 0.0], 0.5)]);
+    let exclusion = Exclusion {
+        negatives: vec![arr1(&[0.0, 1.0, 0.0])],
+        margin: 0.5,
+        action: VetoAction::Penalize(0.3),
+        ..Exclusion::default()
+    };
+    engine.set_exclusion("Stars", Some(exclusion)).unwrap();
+
+    // Close to the negative: 0.707 - 0.3 falls under the threshold
+    let mixed = arr1(&[1.0, 1.0, 0.0]);
+    assert!(engine.find_best_match(&mixed).is_none());
+    let result = &engine.top_k(&mixed, 1).unwrap()[0];
+    assert!((result.similarity - (0.5f32.sqrt() - 0.3)).abs() < 1e-5);
+    assert_eq!(result.veto.as_ref().unwrap().source, VetoSource::Negative(0));
+
+    let clear = arr1(&[1.0, 0.2, 0.0]);
+    assert_eq!(engine.find_best_match(&clear).unwrap().name, "Stars");
+    assert!(engine.top_k(&clear, 1).unwrap()[0].veto.is_none());
+}
+
+#[test]
+fn test_exclusions_are_validated_renamed_and_saved_in_packs() {
+    let mut engine = SimilarityEngine::from_concepts(vec![
+        concept("Aesthetics", [0.0, 1.0, 0.0], 0.5),
+        concept("Formatting", [0.6, 0.8, 0.0], 0.9),
+    ]);
+    let vetoed_by = |name: &str| Some(Exclusion { vetoes: vec![name.to_string()], ..Exclusion::default() });
+    assert!(matches!(engine.set_exclusion("Aesthetics", vetoed_by("Missing")), Err(SimilarityError::InvalidExclusion { .. })));
+    assert!(matches!(engine.set_exclusion("Aesthetics", vetoed_by("Aesthetics")), Err(SimilarityError::InvalidExclusion { .. })));
+    let wrong_size = Exclusion { negatives: vec![arr1(&[1.0, 0.0])], ..Exclusion::default() };
+    assert!(engine.set_exclusion("Aesthetics", Some(wrong_size)).is_err());
+
+    engine.set_exclusion("Aesthetics", vetoed_by("Formatting")).unwrap();
+    let id = engine.id_of("Formatting").unwrap();
+    engine.rename_concept(id, "Code formatting").unwrap();
+    assert_eq!(engine.concept("Aesthetics").unwrap().exclusion.as_ref().unwrap().vetoes, vec!["Code formatting"]);
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("concepts.toml");
+    engine.to_path(&path).unwrap();
+    let reloaded = SimilarityEngine::from_path(&path).unwrap();
+    assert_eq!(reloaded.concept("Aesthetics").unwrap().exclusion, engine.concept("Aesthetics").unwrap().exclusion);
+
+    let mut pack = ConceptPack::load(&path).unwrap();
+    pack.concepts.retain(|c| c.name != "Code formatting");
+    assert!(pack.to_concepts(None).is_err());
+}
+
+#[test]
+fn test_negative_texts_follow_embedder_changes() {
+    let mut engine = SimilarityEngine::with_dimension(64);
+    engine.set_embedder(Box::new(HashingEmbedder::new(64).unwrap())).unwrap();
+    let seeds = |texts: &[&str]| texts.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+    engine.add_seeded_concept("Aesthetics", seeds(&["beautiful colour palette", "elegant visual design"]), vec![]).unwrap();
+    engine.add_veto_text("Aesthetics", "indent the code with four spaces").unwrap();
+
+    engine.set_embedder(Box::new(HashingEmbedder::new(32).unwrap())).unwrap();
+    let exclusion = engine.concept("Aesthetics").unwrap().exclusion.clone().unwrap();
+    assert_eq!(exclusion.text_negatives[0].len(), 32);
+    let formatting = engine.embed("indent the code with four spaces").unwrap();
+    let veto = engine.veto(engine.concept("Aesthetics").unwrap(), &formatting).unwrap().unwrap();
+    assert_eq!(veto.source, VetoSource::Text("indent the code with four spaces".to_string()));
+    assert!(engine.try_find_best_match(&engine.embed("elegant visual design").unwrap()).is_ok());
+
+    // Raw vectors can't be re-embedded, so the embedder change is refused
+    let mut exclusion = exclusion;
+    exclusion.negatives.push(Array1::zeros(32));
+    engine.set_exclusion("Aesthetics", Some(exclusion)).unwrap();
+    let refused = engine.set_embedder(Box::new(HashingEmbedder::new(16).unwrap()));
+    assert!(matches!(refused, Err(SimilarityError::InvalidExclusion { .. })));
+    assert_eq!(engine.dimension(), Some(32));
+
+    // A negative that no longer fits is skipped rather than failing the search
+    let stale = ConceptVector {
+        exclusion: Some(Exclusion { negatives: vec![arr1(&[1.0, 0.0])], margin: 0.0, ..Exclusion::default() }),
+        ..concept("Stars", [1.0, 0.0, 0.0], 0.5)
+    };
+    let engine = SimilarityEngine::from_concepts(vec![stale]);
+    assert_eq!(engine.try_find_best_match(&arr1(&[1.0, 0.1, 0.0])).unwrap().unwrap().name, "Stars");
+}
+
+#[test]
+fn test_vetoes_apply_in_module_views_and_protect_veto_concepts() {
+    let mut engine = SimilarityEngine::from_concepts(vec![
+        concept("Aesthetics", [0.0, 1.0, 0.0], 0.5),
+        concept("Code formatting", [0.6, 0.8, 0.0], 0.99),
+    ]);
+    engine.set_exclusion("Aesthetics", Some(Exclusion { vetoes: vec!["Code formatting".to_string()], ..Exclusion::default() })).unwrap();
+    let registry = engine.shared_registry();
+    let mut actions = ActionSystem::new();
+    for concept in engine.concepts() {
+        actions.orchestrator.register_module(ModuleAgent::from_registry(&concept.name, registry.clone(), vec![concept.id]));
+    }
+
+    let about_beauty = arr1(&[-0.3, 1.0, 0.0]);
+    let about_formatting = arr1(&[0.6, 0.8, 0.0]);
+    let aesthetics = actions.orchestrator.modules.get_mut("Aesthetics").unwrap();
+    assert!(aesthetics.process_input(&about_formatting).is_none());
+    assert_eq!(aesthetics.process_input(&about_beauty).unwrap().name, "Aesthetics");
+    assert_eq!(actions.orchestrator.route_input(&about_formatting).as_deref(), Some("Code formatting"));
+
+    let formatting = engine.id_of("Code formatting").unwrap();
+    assert!(matches!(engine.retire_concept(formatting), Err(SimilarityError::InvalidOperation { .. })));
+    engine.remove_concept("Code formatting");
+    assert!(engine.concept("Aesthetics").unwrap().exclusion.is_none());
+    let aesthetics = actions.orchestrator.modules.get_mut("Aesthetics").unwrap();
+    assert_eq!(aesthetics.process_input(&about_formatting).unwrap().name, "Aesthetics");
+}
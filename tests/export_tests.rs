@@ -0,0 +1,52 @@
+// #FFD700 Graph Export Tests
+use ndarray::array;
+use starweave_mvp::agent_orchestrator::AgentOrchestrator;
+use starweave_mvp::concepts::{ConceptVector, SimilarityEngine};
+use starweave_mvp::export::{ConceptGraph, ExportOptions};
+use starweave_mvp::module_agent::ModuleAgent;
+
+fn concept(name: &str, vector: ndarray::Array1<f32>, curiosity: f32) -> ConceptVector {
+    ConceptVector { name: name.to_string(), vector, curiosity_score: curiosity, ..ConceptVector::default() }
+}
+
+#[test]
+fn test_dot_and_graphml_export() {
+    let engine = SimilarityEngine::from_concepts(vec![
+        concept("Stars", array![1.0, 0.0], 0.9),
+        concept("\"Nebula\" & <dust>", array![0.9, 0.1], 0.1),
+        concept("Code", array![0.0, 1.0], 0.5),
+    ]);
+
+    let mut orchestrator = AgentOrchestrator::new();
+    orchestrator.register_module(ModuleAgent::new("Astronomy", vec![concept("Stars", array![1.0, 0.0], 0.9)]));
+    orchestrator.register_module(ModuleAgent::new("Coding", vec![concept("Code", array![0.0, 1.0], 0.5)]));
+    orchestrator.knowledge_graph.record_co_creation("Coding", "Astronomy");
+    orchestrator.knowledge_graph.record_co_creation("Astronomy", "Coding");
+
+    let graph = ConceptGraph::build(&engine, &orchestrator, ExportOptions::default());
+    // Only the two near-parallel concepts clear the cutoff
+    assert_eq!(graph.similarities.len(), 1);
+    assert_eq!(graph.modules.iter().map(|m| m.name.as_str()).collect::<Vec<_>>(), ["Astronomy", "Coding"]);
+    assert_eq!(graph.co_creations, vec![("Astronomy".to_string(), "Coding".to_string(), 2)]);
+
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("graph starweave {"));
+    assert!(dot.contains("\"Stars\" -- \"\\\"Nebula\\\" & <dust>\""));
+    assert!(dot.contains("\"module:Astronomy\" -- \"Stars\" [style=dotted]"));
+    assert!(dot.contains("label=\"2×\""));
+
+    let graphml = graph.to_graphml();
+    assert!(graphml.contains("&quot;Nebula&quot; &amp; &lt;dust&gt;"));
+    assert_eq!(graphml.matches("<node ").count(), 5);
+    assert_eq!(graphml.matches("<edge ").count(), 4); // 2 memberships, 1 similarity, 1 co-creation
+
+    let none = ConceptGraph::build(&engine, &orchestrator, ExportOptions { similarity_cutoff: 0.999, include_modules: false });
+    assert!(none.similarities.is_empty() && none.modules.is_empty() && none.co_creations.is_empty());
+
+    let dir = std::env::temp_dir();
+    assert!(graph.write(dir.join("starweave_export_test.txt")).is_err());
+    let path = dir.join("starweave_export_test.graphml");
+    graph.write(&path).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), graphml);
+    std::fs::remove_file(path).unwrap();
+}
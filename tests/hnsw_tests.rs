@@ -0,0 +1,69 @@
+// #FF69B4 HNSW Index Tests
+use starweave_mvp::concepts::{ConceptVector, HnswConfig, HnswIndex, SimilarityEngine};
+use ndarray::{Array1, Array2};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+fn random_matrix(rows: usize, cols: usize, seed: u64) -> Array2<f32> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    Array2::from_shape_fn((rows, cols), |_| rng.gen_range(-1.0..1.0))
+}
+
+fn engine_over(vectors: &Array2<f32>, config: HnswConfig) -> SimilarityEngine {
+    let concepts = vectors.rows().into_iter().enumerate()
+        .map(|(i, row)| ConceptVector {
+            name: format!("concept-{i}"),
+            vector: row.to_owned(),
+            threshold: 0.0,
+            ..ConceptVector::default()
+        })
+        .collect();
+    SimilarityEngine::from_concepts(concepts).with_index(config)
+}
+
+#[test]
+fn test_index_recall_against_exact_search() {
+    let engine = engine_over(&random_matrix(2000, 32, 1), HnswConfig { exact_below: 100, ef_construction: 100, ..HnswConfig::default() });
+    let queries = random_matrix(50, 32, 2);
+
+    assert!(engine.uses_index());
+    let recall = engine.index_recall(&queries, 10).unwrap();
+    assert!(recall >= 0.9, "recall@10 was {recall}");
+
+    // The indexed best match agrees with the exact scan on nearly every query
+    let exact = engine_over(&random_matrix(2000, 32, 1), HnswConfig { exact_below: usize::MAX, ..HnswConfig::default() });
+    assert!(!exact.uses_index());
+    let agreeing = queries.rows().into_iter()
+        .filter(|q| {
+            let q = q.to_owned();
+            engine.find_best_match(&q).map(|c| c.name) == exact.find_best_match(&q).map(|c| c.name)
+        })
+        .count();
+    assert!(agreeing >= 45, "only {agreeing}/50 best matches agreed");
+}
+
+#[test]
+fn test_incremental_insert_and_remove() {
+    let vectors = random_matrix(200, 8, 3);
+    let mut index = HnswIndex::from_rows(HnswConfig::default(), &vectors);
+    assert_eq!(index.len(), 200);
+
+    let probe = vectors.row(42).to_owned();
+    assert_eq!(index.search(&probe, 1)[0].0, 42);
+
+    assert!(index.remove(42));
+    assert!(!index.contains(42));
+    assert!(index.search(&probe, 5).iter().all(|&(key, _)| key != 42));
+
+    index.insert(500, probe.view());
+    assert_eq!(index.search(&probe, 1)[0].0, 500);
+
+    // Engine removal keeps index keys aligned with concept positions
+    let mut engine = engine_over(&vectors, HnswConfig { exact_below: 10, ..HnswConfig::default() });
+    let target: Array1<f32> = vectors.row(150).to_owned();
+    assert_eq!(engine.find_best_match(&target).unwrap().name, "concept-150");
+    engine.remove_concept("concept-3").unwrap();
+    assert_eq!(engine.find_best_match(&target).unwrap().name, "concept-150");
+    engine.remove_concept("concept-150").unwrap();
+    assert_ne!(engine.find_best_match(&target).unwrap().name, "concept-150");
+}
@@ -0,0 +1,49 @@
+// #00CED1 Knowledge Graph Tests
+use starweave_mvp::actions::ActionSystem;
+use starweave_mvp::concepts::ConceptVector;
+use starweave_mvp::knowledge_graph::{EdgeKind, KnowledgeGraph, NodeKey};
+use starweave_mvp::module_agent::ModuleAgent;
+
+fn concept(name: &str) -> ConceptVector {
+    ConceptVector { name: name.to_string(), ..ConceptVector::default() }
+}
+
+#[test]
+fn test_actions_and_co_creation_grow_the_graph() {
+    let mut actions = ActionSystem::new();
+    for name in ["Curiosity", "Aesthetics"] {
+        actions.orchestrator.register_module(ModuleAgent::new(name, vec![concept(name)]));
+    }
+
+    actions.trigger_action(&concept("Curiosity"), "Why do stars twinkle?");
+    actions.trigger_action(&concept("Aesthetics"), "why do stars  TWINKLE?");
+    actions.toggle_co_creation();
+    actions.trigger_action(&concept("Curiosity"), "Paint the nebula");
+
+    let graph = &actions.orchestrator.knowledge_graph;
+    let curiosity = NodeKey::Concept("Curiosity".to_string());
+    assert_eq!(graph.node(&curiosity).unwrap().visits, 2);
+    assert!(graph.node(&NodeKey::Episode(2)).is_some());
+
+    // The shared (normalized) input links the two concepts; co-creation links their modules
+    let twinkle = NodeKey::Input("why do stars twinkle?".to_string());
+    assert_eq!(graph.neighbours(&twinkle).iter().filter(|n| matches!(n.key, NodeKey::Concept(_))).count(), 2);
+    assert!(!graph.neighbours(&curiosity).iter().any(|n| n.kind == EdgeKind::CoCreated));
+    let module = NodeKey::Module("Curiosity".to_string());
+    assert!(graph.neighbours(&module).iter().any(|n| n.kind == EdgeKind::CoCreated && n.key == NodeKey::Module("Aesthetics".to_string())));
+    assert!(graph.edges().iter().any(|e| e.kind == EdgeKind::SuggestedBy && e.to == NodeKey::Module("Aesthetics".to_string())));
+    assert_eq!(graph.node(&NodeKey::Concept("Aesthetics".to_string())).unwrap().visits, 1); // Not merged with its module
+    assert_eq!(graph.related_concepts("Curiosity", 5)[0].0, "Aesthetics");
+    assert!(graph.neighbourhood(&curiosity, 2).iter().any(|(key, hops)| *key == twinkle && *hops == 1));
+
+    // Unmatched inputs are kept without inventing a concept for them
+    actions.trigger_default_action("Hum a lullaby");
+    let graph = &actions.orchestrator.knowledge_graph;
+    let lullaby = NodeKey::Input("hum a lullaby".to_string());
+    assert!(graph.node(&lullaby).is_some() && graph.neighbours(&lullaby).is_empty());
+    assert!(graph.node(&NodeKey::Concept("Default".to_string())).is_none());
+
+    let copy = KnowledgeGraph::from_json(&graph.to_json().unwrap()).unwrap();
+    assert_eq!((copy.node_count(), copy.edge_count()), (graph.node_count(), graph.edge_count()));
+    assert_eq!(copy.neighbours(&curiosity), graph.neighbours(&curiosity));
+}
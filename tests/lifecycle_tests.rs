@@ -0,0 +1,174 @@
+// #FF69B4 Concept Lifecycle Tests
+use ndarray::{array, Array1};
+use starweave_mvp::concepts::{ConceptEvent, Exclusion, ConceptVector, SimilarityEngine, SimilarityError};
+use starweave_mvp::embedding::HashingEmbedder;
+use starweave_mvp::module_agent::ModuleAgent;
+
+fn concept(name: &str, vector: Array1<f32>, threshold: f32) -> ConceptVector {
+    ConceptVector { name: name.to_string(), vector, threshold, ..ConceptVector::default() }
+}
+
+#[test]
+fn test_ids_names_edits_and_retirement() {
+    let mut engine = SimilarityEngine::new();
+    let ids: Vec<_> = engine.concepts().iter().map(|c| c.id).collect();
+    assert!(ids.iter().all(|id| id.is_assigned()) && ids[0] != ids[1]);
+
+    assert!(matches!(
+        engine.add_concept(concept("Curiosity", array![1.0, 0.0, 0.0], 0.5)),
+        Err(SimilarityError::DuplicateConcept { .. })
+    ));
+    let doubled = vec![concept("A", array![1.0], 0.5), concept("A", array![1.0], 0.5)];
+    assert!(SimilarityEngine::try_from_concepts(doubled).is_err());
+
+    let child = engine.add_concept(ConceptVector { parent: Some("Curiosity".to_string()), ..concept("Wonder", array![0.9, -0.2, 0.4], 0.9) }).unwrap();
+    engine.rename_concept(ids[0], "Inquiry").unwrap();
+    assert_eq!(engine.concept_by_id(child).unwrap().parent.as_deref(), Some("Inquiry"));
+    assert!(engine.rename_concept(ids[0], "Aesthetics").is_err());
+    // Rejected edits leave the concept as it was
+    assert!(engine.edit_concept(ids[0], |c| c.vector = array![1.0, 0.0]).is_err());
+    assert_eq!(engine.concept_by_id(ids[0]).unwrap().vector.len(), 3);
+    assert!(engine.edit_concept(ids[0], |c| c.parent = Some("Wonder".to_string())).is_err());
+    assert_eq!(engine.concept_by_id(ids[0]).unwrap().parent, None);
+
+    let query = array![0.9, -0.2, 0.5];
+    assert!(engine.retire_concept(ids[0]).is_err()); // Wonder still refines it
+    engine.retire_concept(child).unwrap();
+    engine.retire_concept(ids[0]).unwrap();
+    assert_eq!(engine.retired_concepts().len(), 2);
+    assert!(engine.find_best_match(&query).is_none_or(|c| c.id != ids[0]));
+    assert!(engine.add_concept(concept("Inquiry", array![0.0, 1.0, 0.0], 0.5)).is_err());
+    assert!(engine.restore_concept(child).is_err()); // Its parent is still retired
+    engine.restore_concept(ids[0]).unwrap();
+    assert_eq!(engine.find_best_match(&query).unwrap().id, ids[0]);
+}
+
+#[test]
+fn test_merge_and_split_from_matched_history() {
+    let mut engine = SimilarityEngine::from_concepts(vec![
+        concept("Space", array![1.0, 0.2, 0.0], 0.8),
+        concept("Astronomy", array![1.0, -0.2, 0.0], 0.6),
+        concept("Cooking", array![0.0, 0.0, 1.0], 0.8),
+    ]);
+    let (space, astronomy) = (engine.id_of("Space").unwrap(), engine.id_of("Astronomy").unwrap());
+    let events = engine.subscribe();
+
+    let merged = engine.merge_concepts(space, astronomy).unwrap();
+    assert_eq!((merged.prototype_vectors().len(), merged.threshold), (2, 0.6));
+    assert!(engine.concept("Astronomy").is_none());
+    assert_eq!(engine.find_best_match(&array![1.0, -0.2, 0.0]).unwrap().name, "Space");
+
+    assert!(engine.split_concept(space, &["Stars", "Planets"]).is_err()); // No history yet
+    for i in 0..6 {
+        let wobble = i as f32 * 0.01;
+        engine.record_match(space, &array![1.0, 0.5 + wobble, 0.0]).unwrap();
+        engine.record_match(space, &array![1.0, -0.5 - wobble, 0.0]).unwrap();
+    }
+    assert_eq!(engine.match_history(space), 12);
+    let parts = engine.split_concept(space, &["Stars", "Planets"]).unwrap();
+    assert!(engine.concept_by_id(space).is_none());
+    let upper = engine.find_best_match(&array![1.0, 0.52, 0.0]).unwrap();
+    let lower = engine.find_best_match(&array![1.0, -0.52, 0.0]).unwrap();
+    assert!(parts.contains(&upper.id) && parts.contains(&lower.id) && upper.id != lower.id);
+    assert_eq!(engine.match_history(upper.id), 6);
+
+    let received: Vec<ConceptEvent> = events.try_iter().collect();
+    assert!(matches!(received[0], ConceptEvent::Merged { absorbed, .. } if absorbed == astronomy));
+    assert!(matches!(&received[1], ConceptEvent::Split { from, into } if *from == space && into.len() == 2));
+}
+
+#[test]
+fn test_modules_follow_engine_changes() {
+    let mut engine = SimilarityEngine::from_concepts(vec![
+        concept("Space", array![1.0, 0.0], 0.8),
+        concept("Astronomy", array![0.9, 0.1], 0.8),
+        concept("Cooking", array![0.0, 1.0], 0.8),
+    ]);
+    let registry = engine.shared_registry();
+    let mut modules: Vec<ModuleAgent> = engine.concepts().iter()
+        .map(|c| ModuleAgent::from_registry(&c.name, registry.clone(), vec![c.id]))
+        .collect();
+    let mut cooking = modules.pop().unwrap();
+    let mut astronomy = modules.pop().unwrap();
+    let mut space = modules.pop().unwrap();
+
+    let id = engine.id_of("Cooking").unwrap();
+    engine.edit_concept(id, |c| c.threshold = 0.1).unwrap();
+    engine.merge_concepts(engine.id_of("Space").unwrap(), engine.id_of("Astronomy").unwrap()).unwrap();
+
+    assert!(cooking.sync());
+    assert!(!cooking.sync());
+    assert_eq!(cooking.concepts()[0].threshold, 0.1);
+    assert!(cooking.process_input(&array![0.7, 0.7]).is_some());
+    assert_eq!(space.concepts()[0].prototype_vectors().len(), 2);
+    // The absorbed concept's module now covers the merged one
+    assert!(astronomy.process_input(&array![0.9, 0.1]).is_some_and(|c| c.name == "Space"));
+
+    engine.retire_concept(id).unwrap();
+    assert!(cooking.concepts().is_empty() && cooking.process_input(&array![0.0, 1.0]).is_none());
+    engine.restore_concept(id).unwrap();
+    assert!(cooking.process_input(&array![0.0, 1.0]).is_some());
+    assert!(space.sync());
+}
+
+#[test]
+fn test_retired_concepts_follow_embedder_changes_and_refused_restores_keep_them() {
+    let mut engine = SimilarityEngine::with_dimension(64);
+    engine.set_embedder(Box::new(HashingEmbedder::new(64).unwrap())).unwrap();
+    let seeds = |texts: &[&str]| texts.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+    engine.add_seeded_concept("Stars", seeds(&["bright stars at night", "distant galaxies"]), vec![]).unwrap();
+    let code = engine.add_seeded_concept("Code", seeds(&["compile the program", "fix the failing test"]), vec![]).unwrap();
+    let raw = engine.add_concept(concept("Raw", Array1::ones(64), 0.5)).unwrap();
+    engine.retire_concept(code).unwrap();
+    engine.retire_concept(raw).unwrap();
+
+    engine.set_embedder(Box::new(HashingEmbedder::new(32).unwrap())).unwrap();
+    // The hand-authored vector can't move to the new size, and stays retired
+    assert!(matches!(engine.restore_concept(raw), Err(SimilarityError::DimensionMismatch { .. })));
+    assert!(engine.retired_concepts().iter().any(|c| c.id == raw));
+
+    engine.restore_concept(code).unwrap();
+    assert_eq!(engine.concept_by_id(code).unwrap().vector.len(), 32);
+    assert_eq!(engine.find_best_match_text("compile the program").unwrap().unwrap().name, "Code");
+}
+
+#[test]
+fn test_merge_split_and_restore_keep_taxonomy_and_vetoes_valid() {
+    let mut engine = SimilarityEngine::from_concepts(vec![
+        concept("B", array![1.0, 0.0], 0.8),
+        ConceptVector { parent: Some("B".to_string()), ..concept("C", array![0.9, 0.1], 0.8) },
+        ConceptVector { parent: Some("C".to_string()), ..concept("A", array![0.8, 0.2], 0.8) },
+        concept("Cooking", array![0.0, 1.0], 0.8),
+    ]);
+    let (a, b) = (engine.id_of("A").unwrap(), engine.id_of("B").unwrap());
+    // C would end up under A, its own child
+    assert!(matches!(engine.merge_concepts(a, b), Err(SimilarityError::InvalidOperation { .. })));
+    assert!(engine.concept("B").is_some() && engine.validate_taxonomy().is_ok());
+    assert_eq!(engine.path_of("A").as_deref(), Some("B/C/A"));
+
+    let cooking = engine.id_of("Cooking").unwrap();
+    engine.set_exclusion("B", Some(Exclusion { vetoes: vec!["Cooking".to_string()], ..Exclusion::default() })).unwrap();
+    for i in 0..4 {
+        engine.record_match(cooking, &array![0.1 * i as f32, 1.0]).unwrap();
+        engine.record_match(cooking, &array![-0.1 * i as f32, 1.0]).unwrap();
+    }
+    assert!(engine.split_concept(cooking, &["Baking", "Frying"]).is_err()); // B still vetoes it
+    engine.split_concept(cooking, &["Cooking", "Frying"]).unwrap();
+    assert_eq!(engine.match_history(cooking), 0);
+    assert!(engine.concept("B").unwrap().exclusion.is_some());
+}
+
+#[test]
+fn test_restore_rechecks_exclusion_rules() {
+    let mut engine = SimilarityEngine::with_dimension(64);
+    engine.set_embedder(Box::new(HashingEmbedder::new(64).unwrap())).unwrap();
+    let code = engine.add_seeded_concept("Code", vec!["compile the program".to_string()], vec![]).unwrap();
+    engine.add_seeded_concept("Stars", vec!["bright stars at night".to_string()], vec![]).unwrap();
+    let exclusion = Exclusion { negatives: vec![Array1::ones(64)], ..Exclusion::default() };
+    engine.set_exclusion("Code", Some(exclusion)).unwrap();
+    engine.retire_concept(code).unwrap();
+
+    engine.set_embedder(Box::new(HashingEmbedder::new(32).unwrap())).unwrap();
+    assert!(matches!(engine.restore_concept(code), Err(SimilarityError::DimensionMismatch { .. })));
+    assert!(engine.retired_concepts().iter().any(|c| c.id == code));
+}
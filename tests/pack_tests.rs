@@ -0,0 +1,186 @@
+// #FF69B4 Concept Pack Tests
+use starweave_mvp::concepts::{ConceptPack, PackError, SimilarityEngine};
+use starweave_mvp::embedding::HashingEmbedder;
+
+const TOML_PACK: &str = r#"
+[[concepts]]
+name = "Curiosity"
+vector = [0.9, -0.2, 0.5]
+threshold = 0.7
+curiosity_score = 0.85
+
+[concepts.metadata]
+action = "research"
+
+[[concepts]]
+name = "Verification"
+vector = [-0.3, 0.1, 0.9]
+threshold = 0.75
+"#;
+
+#[test]
+fn test_pack_round_trips_through_json_and_toml() {
+    let dir = tempfile::tempdir().unwrap();
+    let toml_path = dir.path().join("concepts.toml");
+    std::fs::write(&toml_path, TOML_PACK).unwrap();
+
+    let engine = SimilarityEngine::from_path(&toml_path).unwrap();
+    assert_eq!(engine.concepts().len(), 2);
+    assert_eq!(engine.dimension(), Some(3));
+    assert_eq!(engine.concepts()[0].metadata["action"], "research");
+    assert_eq!(engine.concepts()[1].curiosity_score, 0.5);
+
+    let json_path = dir.path().join("concepts.json");
+    engine.to_path(&json_path).unwrap();
+    let reloaded = SimilarityEngine::from_path(&json_path).unwrap();
+    assert_eq!(reloaded.to_pack(), engine.to_pack());
+
+    let toml_copy = dir.path().join("copy.toml");
+    reloaded.to_path(&toml_copy).unwrap();
+    assert_eq!(ConceptPack::load(&toml_copy).unwrap(), engine.to_pack());
+}
+
+#[test]
+fn test_validation_points_to_offending_entry() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("bad.json");
+    std::fs::write(&path, r#"{"concepts": [
+        {"name": "Curiosity", "vector": [0.9, -0.2, 0.5]},
+        {"name": "Aesthetics", "vector": [0.2, 0.8]}
+    ]}"#).unwrap();
+
+    let err = SimilarityEngine::from_path(&path).err().unwrap();
+    assert!(matches!(&err, PackError::InvalidEntry { index: 1, name, .. } if name == "Aesthetics"));
+    assert!(err.to_string().contains("concept #1 ('Aesthetics')"));
+
+    std::fs::write(&path, r#"{"concepts": [
+        {"name": "Curiosity", "vector": [0.9, -0.2, 0.5]},
+        {"name": "Curiosity", "vector": [0.9, -0.2, 0.5]}
+    ]}"#).unwrap();
+    assert!(matches!(SimilarityEngine::from_path(&path), Err(PackError::InvalidEntry { index: 1, .. })));
+
+    assert!(matches!(
+        SimilarityEngine::from_path(dir.path().join("concepts.yaml")),
+        Err(PackError::UnsupportedFormat { .. })
+    ));
+}
+
+#[test]
+fn test_seed_texts_are_embedded_with_the_given_embedder() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("seeded.toml");
+    std::fs::write(&path, r#"
+[[concepts]]
+name = "Verification"
+seed_texts = ["check the facts", "run the unit tests"]
+threshold = 0.3
+"#).unwrap();
+
+    assert!(matches!(SimilarityEngine::from_path(&path), Err(PackError::InvalidEntry { index: 0, .. })));
+
+    let engine = SimilarityEngine::from_path_with_embedder(&path, Box::new(HashingEmbedder::new(64).unwrap())).unwrap();
+    assert_eq!(engine.dimension(), Some(64));
+    assert_eq!(engine.embedder().unwrap().dimension(), 64);
+    let found = engine.find_best_match_text("check the facts").unwrap();
+    assert_eq!(found.map(|c| c.name), Some("Verification".to_string()));
+
+    // A saved pack records 64 dimensions, but its seeds re-embed under a smaller model
+    let saved = dir.path().join("saved.toml");
+    engine.to_pack().save(&saved).unwrap();
+    let smaller = SimilarityEngine::from_path_with_embedder(&saved, Box::new(HashingEmbedder::new(32).unwrap())).unwrap();
+    assert_eq!(smaller.dimension(), Some(32));
+    assert_eq!(smaller.find_best_match_text("check the facts").unwrap().map(|c| c.name), Some("Verification".to_string()));
+}
+
+#[test]
+fn test_multi_prototype_concepts_match_each_exemplar_and_serialize() {
+    let pack = r#"
+[[concepts]]
+name = "Verification"
+prototypes = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]
+threshold = 0.9
+aggregation = { policy = "soft_max", temperature = 0.1 }
+"#;
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("multi.toml");
+    std::fs::write(&path, pack).unwrap();
+    let engine = SimilarityEngine::from_path(&path).unwrap();
+
+    // Both exemplars fire on their own, though the centroid alone would not pass 0.9
+    for (query, exemplar) in [([0.98, 0.1, 0.0], 0), ([0.1, 0.98, 0.0], 1)] {
+        let query = ndarray::Array1::from_vec(query.to_vec());
+        let ranked = engine.top_k(&query, 1).unwrap();
+        assert!(ranked[0].passed_threshold, "query {query} missed");
+        assert_eq!(ranked[0].prototype, Some(exemplar));
+    }
+
+    for file in ["multi.json", "multi-copy.toml"] {
+        let copy = dir.path().join(file);
+        engine.to_path(&copy).unwrap();
+        let reloaded = SimilarityEngine::from_path(&copy).unwrap();
+        assert_eq!(reloaded.concepts()[0].prototypes, engine.concepts()[0].prototypes);
+        assert_eq!(reloaded.to_pack(), engine.to_pack());
+    }
+}
+
+#[test]
+fn test_taxonomy_inherits_and_refines_from_parent_to_child() {
+    use starweave_mvp::actions::ActionSystem;
+
+    let pack = r#"
+[[concepts]]
+name = "Verification"
+vector = [1.0, 0.0, 0.0]
+threshold = 0.6
+
+[concepts.metadata]
+action = "verify"
+owner = "core"
+
+[[concepts]]
+name = "Code review"
+parent = "Verification"
+vector = [0.8, 0.6, 0.0]
+
+[concepts.metadata]
+owner = "tooling"
+
+[[concepts]]
+name = "Fact check"
+parent = "Verification"
+vector = [0.8, 0.0, 0.6]
+threshold = 0.95
+"#;
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("tree.toml");
+    std::fs::write(&path, pack).unwrap();
+    let engine = SimilarityEngine::from_path(&path).unwrap();
+
+    assert_eq!(engine.concepts()[1].threshold, 0.6); // Inherited
+    assert_eq!(engine.concepts()[2].threshold, 0.95); // Own value wins
+    let metadata = engine.inherited_metadata("Code review");
+    assert_eq!((metadata["action"].as_str(), metadata["owner"].as_str()), ("verify", "tooling"));
+    assert_eq!(engine.path_of("Fact check").unwrap(), "Verification/Fact check");
+
+    let query = ndarray::Array1::from_vec(vec![0.9, 0.5, 0.0]);
+    let matched = engine.try_find_hierarchical_match(&query).unwrap().unwrap();
+    assert_eq!((matched.path.as_str(), matched.depth), ("Verification/Code review", 1));
+    assert_eq!(engine.top_k(&query, 1).unwrap()[0].path, "Verification/Code review");
+
+    // Fact check is the closer child here but misses its strict threshold, so we stop at the root
+    let query = ndarray::Array1::from_vec(vec![0.9, -0.3, 0.3]);
+    assert_eq!(engine.try_find_hierarchical_match(&query).unwrap().unwrap().path, "Verification");
+
+    let mut actions = ActionSystem::new();
+    let response = actions.trigger_path_action(&matched.path, &matched.concept, "review this diff");
+    assert!(response.contains("Verification (Code review)"));
+    assert!(actions.get_recent_actions()[0].starts_with("[Verification/Code review]"));
+
+    // Unknown parents and cycles are rejected
+    let broken = pack.replace("parent = \"Verification\"\nvector = [0.8, 0.6", "parent = \"Nope\"\nvector = [0.8, 0.6");
+    std::fs::write(&path, broken).unwrap();
+    assert!(matches!(SimilarityEngine::from_path(&path), Err(PackError::InvalidEntry { index: 1, .. })));
+    let mut cyclic = engine.to_pack();
+    cyclic.concepts[0].parent = Some("Code review".to_string());
+    assert!(SimilarityEngine::from_pack(&cyclic, None).is_err());
+}
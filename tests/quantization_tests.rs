@@ -0,0 +1,56 @@
+// #FF69B4 Vector Quantization Tests
+use starweave_mvp::concepts::{ConceptVector, Quantization, QuantizationConfig, SimilarityEngine};
+use ndarray::Array2;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+fn random_matrix(rows: usize, cols: usize, seed: u64) -> Array2<f32> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    Array2::from_shape_fn((rows, cols), |_| rng.gen_range(-1.0..1.0))
+}
+
+fn engine_over(vectors: &Array2<f32>) -> SimilarityEngine {
+    let concepts = vectors.rows().into_iter().enumerate()
+        .map(|(i, row)| ConceptVector { name: format!("concept-{i}"), vector: row.to_owned(), threshold: 0.0, ..ConceptVector::default() })
+        .collect();
+    SimilarityEngine::from_concepts(concepts)
+}
+
+#[test]
+fn test_quantized_search_with_rescoring_matches_exact_search() {
+    let vectors = random_matrix(2000, 64, 1);
+    let queries = random_matrix(40, 64, 2);
+    let exact = engine_over(&vectors);
+    exact.find_best_match(&queries.row(0).to_owned());
+    assert_eq!(exact.memory_bytes(), 2 * 2000 * 64 * 4); // Vectors plus the cached matrix
+
+    let product = Quantization::Product { subspaces: 16, centroids: 64 };
+    for method in [Quantization::Int8, product] {
+        let mut engine = engine_over(&vectors).with_quantization(QuantizationConfig { method, exact_below: 100, ..QuantizationConfig::default() });
+        assert!(engine.uses_quantization());
+
+        let (quantized, full) = engine.quantized_memory().unwrap().unwrap();
+        assert_eq!(full, 2000 * 64 * 4);
+        assert!(quantized * 3 < full, "{method:?} used {quantized} of {full} bytes");
+        // Only the codes sit beside the vectors: no f32 matrix is cached as well
+        engine.find_best_match(&queries.row(0).to_owned());
+        engine.quantization_recall(&queries, 1).unwrap();
+        assert_eq!(engine.memory_bytes(), 2000 * 64 * 4 + quantized);
+
+        let recall = engine.quantization_recall(&queries, 5).unwrap();
+        assert!(recall >= 0.8, "{method:?} recall@5 was {recall}");
+        let agreeing = queries.rows().into_iter()
+            .filter(|q| {
+                let q = q.to_owned();
+                engine.find_best_match(&q).map(|c| c.name) == exact.find_best_match(&q).map(|c| c.name)
+            })
+            .count();
+        assert!(agreeing >= 36, "{method:?}: only {agreeing}/40 best matches agreed");
+
+        // Edited concepts are re-encoded before the next query
+        let id = engine.id_of("concept-7").unwrap();
+        let probe = queries.row(0).to_owned();
+        engine.edit_concept(id, |c| c.vector = probe.clone()).unwrap();
+        assert_eq!(engine.find_best_match(&probe).unwrap().name, "concept-7");
+    }
+}
@@ -0,0 +1,48 @@
+// #FF69B4 Shared Concept Registry Tests
+use ndarray::array;
+use starweave_mvp::actions::ActionSystem;
+use starweave_mvp::concepts::{ConceptVector, LearningConfig, SimilarityEngine};
+use starweave_mvp::module_agent::ModuleAgent;
+
+#[test]
+fn test_engine_modules_and_orchestrator_share_concept_state() {
+    let mut engine = SimilarityEngine::from_concepts(vec![
+        ConceptVector { name: "Stars".to_string(), vector: array![1.0, 0.0], threshold: 0.5, curiosity_score: 0.2, ..ConceptVector::default() },
+        ConceptVector { name: "Code".to_string(), vector: array![0.0, 1.0], threshold: 0.5, curiosity_score: 0.4, ..ConceptVector::default() },
+    ]);
+    engine.set_learning(Some(LearningConfig { learning_rate: 0.5, ..LearningConfig::default() })).unwrap();
+    let registry = engine.shared_registry();
+    let mut actions = ActionSystem::new();
+    for concept in engine.concepts() {
+        actions.orchestrator.register_module(ModuleAgent::from_registry(&concept.name, registry.clone(), vec![concept.id]));
+    }
+    let stars = engine.id_of("Stars").unwrap();
+    let generation = registry.generation();
+
+    engine.edit_concept(stars, |c| c.curiosity_score = 0.9).unwrap();
+    engine.confirm_match("Stars", &array![0.8, 0.6]).unwrap();
+    assert_eq!(registry.generation(), generation + 2);
+    // Interaction times are patched in place: no generation bump, so views aren't rebuilt
+    engine.update_concept_after_interaction("Stars");
+    assert_eq!(registry.generation(), generation + 2);
+    let touched = engine.concept_by_id(stars).unwrap().last_interaction_time;
+    assert_eq!(registry.get(stars).unwrap().last_interaction_time, touched);
+
+    let shared = registry.get(stars).unwrap();
+    let module = &actions.orchestrator.modules["Stars"];
+    assert_eq!(shared.vector, engine.concept_by_id(stars).unwrap().vector);
+    assert_eq!(module.concepts()[0].curiosity_score, 0.9);
+    assert!(module.concepts()[0].learned.is_some());
+    // Routing refreshes the module view, so the learned vector is what gets compared
+    let routed = actions.orchestrator.route_input(&engine.concept_by_id(stars).unwrap().vector);
+    assert_eq!(routed.as_deref(), Some("Stars"));
+    let matched = actions.orchestrator.modules.get_mut("Stars").unwrap().process_input(&shared.vector).unwrap();
+    assert_eq!(matched.last_interaction_time, touched);
+    assert_eq!(actions.orchestrator.modules["Stars"].local_engine.concepts()[0].vector, shared.vector);
+    let suggestion = actions.orchestrator.modules["Stars"].suggest_concept("Code").map(|c| c.curiosity_score);
+    assert_eq!(suggestion, Some(0.9));
+
+    engine.remove_concept("Code");
+    assert!(registry.get_by_name("Code").is_none());
+    assert!(actions.orchestrator.modules["Code"].concepts().is_empty());
+}
@@ -0,0 +1,61 @@
+// #FF69B4 Exact Scan Tests
+use starweave_mvp::concepts::{cosine_similarity, ConceptVector, SimilarityEngine};
+use ndarray::{Array1, Array2};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+#[test]
+fn test_matrix_scan_agrees_with_cosine_similarity() {
+    // Large enough to be split into several chunks when the `parallel` feature is on
+    let mut rng = StdRng::seed_from_u64(3);
+    let concepts: Vec<ConceptVector> = (0..10_000)
+        .map(|i| ConceptVector {
+            name: format!("concept-{i}"),
+            vector: Array1::from_shape_fn(32, |_| rng.gen_range(-1.0..1.0)),
+            threshold: 0.2,
+            ..ConceptVector::default()
+        })
+        .collect();
+    let engine = SimilarityEngine::from_concepts(concepts.clone());
+    let queries = Array2::from_shape_fn((8, 32), |_| rng.gen_range(-1.0..1.0));
+
+    let scores = engine.score_batch(&queries).unwrap();
+    let matches = engine.match_batch(&queries).unwrap();
+    for (q, query) in queries.rows().into_iter().enumerate() {
+        let query = query.to_owned();
+        let expected: Vec<f32> = concepts.iter().map(|c| cosine_similarity(&c.vector, &query)).collect();
+        for (i, &similarity) in expected.iter().enumerate() {
+            assert!((scores[[q, i]] - similarity).abs() < 1e-5, "query {q}, concept {i}");
+        }
+
+        let best = expected.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1)).map(|(i, _)| i).unwrap();
+        assert_eq!(engine.find_best_match(&query).unwrap().name, concepts[best].name);
+        assert_eq!(matches[q].unwrap().concept, best);
+    }
+
+    // A zero query is similar to nothing, as with `cosine_similarity`
+    assert!(engine.find_best_match(&Array1::zeros(32)).is_none());
+}
+
+#[test]
+fn test_matrix_follows_edits_that_keep_the_concept_count() {
+    let concept = |name: &str, vector: Vec<f32>| ConceptVector { name: name.to_string(), vector: Array1::from_vec(vector), threshold: 0.5, ..ConceptVector::default() };
+    let mut engine = SimilarityEngine::from_concepts(vec![concept("Left", vec![1.0, 0.0]), concept("Right", vec![0.0, 1.0])]);
+    let query = Array1::from_vec(vec![0.0, 1.0]);
+    assert_eq!(engine.find_best_match(&query).unwrap().name, "Right"); // Builds the cached matrix
+
+    let right = engine.id_of("Right").unwrap();
+    engine.edit_concept(right, |c| c.vector = Array1::from_vec(vec![1.0, 0.0])).unwrap();
+    let left = engine.id_of("Left").unwrap();
+    engine.edit_concept(left, |c| c.vector = Array1::from_vec(vec![0.0, 1.0])).unwrap();
+    assert_eq!(engine.find_best_match(&query).unwrap().name, "Left");
+    assert_eq!(engine.score_batch(&query.insert_axis(ndarray::Axis(0))).unwrap()[[0, 1]], 0.0);
+}
+
+#[test]
+fn test_empty_engine_scores_batches_without_panicking() {
+    let engine = SimilarityEngine::from_concepts(vec![]);
+    let inputs = Array2::zeros((2, 3));
+    assert_eq!(engine.score_batch(&inputs).unwrap().dim(), (2, 0));
+    assert_eq!(engine.match_batch(&inputs).unwrap(), vec![None, None]);
+}
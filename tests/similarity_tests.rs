@@ -0,0 +1,324 @@
+// #FF69B4 Similarity Engine Tests
+use starweave_mvp::concepts::{ConceptVector, SimilarityEngine, SimilarityError};
+use starweave_mvp::embedding::HashingEmbedder;
+use ndarray::Array1;
+
+fn concept(name: &str, vector: Vec<f32>, threshold: f32) -> ConceptVector {
+    ConceptVector {
+        name: name.to_string(),
+        vector: Array1::from_vec(vector),
+        threshold,
+        ..ConceptVector::default()
+    }
+}
+
+#[test]
+fn test_mismatched_query_is_an_error_not_a_panic() {
+    let engine = SimilarityEngine::new();
+    assert_eq!(engine.dimension(), Some(3));
+
+    let query = Array1::zeros(384);
+    let err = engine.try_find_best_match(&query).unwrap_err();
+    assert!(matches!(err, SimilarityError::DimensionMismatch { expected: 3, found: 384, .. }));
+    assert!(engine.find_best_match(&query).is_none());
+}
+
+#[test]
+fn test_engine_rejects_concepts_and_embedders_of_wrong_size() {
+    let mut engine = SimilarityEngine::with_dimension(2);
+    assert!(engine.add_concept(concept("Flat", vec![1.0, 0.0], 0.5)).is_ok());
+    assert!(matches!(
+        engine.add_concept(concept("Deep", vec![1.0, 0.0, 0.0], 0.5)),
+        Err(SimilarityError::DimensionMismatch { expected: 2, found: 3, .. })
+    ));
+
+    let mixed = vec![concept("A", vec![1.0, 0.0], 0.5), concept("B", vec![1.0], 0.5)];
+    assert!(SimilarityEngine::try_from_concepts(mixed).is_err());
+
+    assert!(engine.set_embedder(Box::new(HashingEmbedder::new(64).unwrap())).is_err());
+
+    // An empty engine takes its dimension from the embedder and holds queries to it
+    let mut empty = SimilarityEngine::from_concepts(vec![]);
+    empty.set_embedder(Box::new(HashingEmbedder::new(16).unwrap())).unwrap();
+    assert_eq!(empty.dimension(), Some(16));
+    assert!(matches!(empty.try_find_best_match(&Array1::zeros(3)), Err(SimilarityError::DimensionMismatch { expected: 16, .. })));
+    assert!(empty.match_texts(&["hello"]).unwrap()[0].is_none());
+}
+
+#[test]
+fn test_projection_adapts_embedder_to_stored_concepts() {
+    let mut engine = SimilarityEngine::new();
+    let projected = engine.set_embedder_with_projection(Box::new(HashingEmbedder::new(64).unwrap()), 7).unwrap();
+    assert!(projected);
+
+    let embedder = engine.embedder().unwrap();
+    assert_eq!(embedder.dimension(), 3);
+    assert!(embedder.model_id().starts_with("hashing-ngram-d64"));
+    assert!(engine.find_best_match_text("an adapted query").is_ok());
+}
+
+#[test]
+fn test_score_batch_matches_single_queries() {
+    use ndarray::array;
+    use starweave_mvp::concepts::BatchMatch;
+
+    let engine = SimilarityEngine::new();
+    let inputs = array![
+        [0.85, -0.15, 0.45],
+        [0.2, 0.8, -0.1],
+        [0.0, 0.0, 0.0],
+    ];
+
+    let scores = engine.score_batch(&inputs).unwrap();
+    assert_eq!(scores.dim(), (3, engine.concepts().len()));
+    let expected = starweave_mvp::cosine_similarity(&engine.concepts()[0].vector, &inputs.row(0).to_owned());
+    assert!((scores[[0, 0]] - expected).abs() < 1e-5);
+
+    let matches = engine.match_batch(&inputs).unwrap();
+    for (row, found) in matches.iter().enumerate() {
+        let single = engine.find_best_match(&inputs.row(row).to_owned());
+        assert_eq!(found.map(|m| engine.concepts()[m.concept].name.clone()), single.map(|c| c.name));
+    }
+    assert!(matches!(matches[1], Some(BatchMatch { concept: 1, .. })));
+
+    assert!(engine.score_batch(&ndarray::Array2::zeros((2, 5))).is_err());
+}
+
+#[test]
+fn test_seeded_concepts_follow_the_active_embedder() {
+    use starweave_mvp::concepts::seed::derive_prototype;
+    use starweave_mvp::concepts::{PrototypeStrategy, SimilarityMetric};
+
+    let strings = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    let positives = strings(&["check the facts", "verify the claims", "fact check this claim"]);
+    let negatives = strings(&["paint a sunset", "compose a melody"]);
+
+    let embedder = HashingEmbedder::new(128).unwrap();
+    let prototype = derive_prototype(&embedder, &positives, &negatives, PrototypeStrategy::Centroid, SimilarityMetric::Cosine).unwrap();
+    let negative = prototype.strongest_negative.unwrap();
+    assert!(prototype.weakest_positive > negative);
+    assert!(prototype.suggested_threshold < prototype.weakest_positive);
+    assert!(prototype.suggested_threshold > negative);
+
+    let medoid = derive_prototype(&embedder, &positives, &[], PrototypeStrategy::Medoid, SimilarityMetric::Cosine).unwrap();
+    assert!((medoid.weakest_positive - (medoid.suggested_threshold + 0.05)).abs() < 1e-5);
+
+    let mut engine = SimilarityEngine::with_dimension(128);
+    engine.set_embedder(Box::new(embedder)).unwrap();
+    engine.add_seeded_concept("Verification", positives, negatives).unwrap();
+    assert!(engine.concepts()[0].auto_threshold);
+    assert!(engine.find_best_match_text("verify the facts").unwrap().is_some());
+    assert!(engine.find_best_match_text("paint a sunset").unwrap().is_none());
+
+    // Swapping the embedder re-derives the vector in the new dimension
+    engine.set_embedder(Box::new(HashingEmbedder::new(32).unwrap())).unwrap();
+    assert_eq!(engine.dimension(), Some(32));
+    assert_eq!(engine.concepts()[0].vector.len(), 32);
+    assert!(engine.find_best_match_text("verify the facts").unwrap().is_some());
+}
+
+#[test]
+fn test_merged_seeded_concepts_keep_prototypes_in_the_embedder_space() {
+    let strings = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    let mut engine = SimilarityEngine::with_dimension(64);
+    engine.set_embedder(Box::new(HashingEmbedder::new(64).unwrap())).unwrap();
+    let stars = engine.add_seeded_concept("Stars", strings(&["bright stars at night", "distant galaxies"]), vec![]).unwrap();
+    let planets = engine.add_seeded_concept("Planets", strings(&["the rings of saturn", "mars is red"]), vec![]).unwrap();
+    engine.merge_concepts(stars, planets).unwrap();
+
+    engine.set_embedder(Box::new(HashingEmbedder::new(32).unwrap())).unwrap();
+    let merged = engine.concept_by_id(stars).unwrap();
+    assert!(merged.prototype_vectors().iter().all(|p| p.len() == 32));
+    assert!(engine.find_best_match_text("distant galaxies").unwrap().is_some_and(|c| c.id == stars));
+    assert!(engine.top_k(&engine.embed("mars is red").unwrap(), 1).is_ok());
+}
+
+#[test]
+fn test_top_k_ranks_all_candidates_and_explains_the_winner() {
+    // "Strict" is the closest concept but its threshold is too high to fire
+    let engine = SimilarityEngine::from_concepts(vec![
+        concept("Strict", vec![1.0, 0.0], 0.99),
+        concept("Loose", vec![0.8, 0.6], 0.5),
+        concept("Opposite", vec![-1.0, 0.0], 0.5),
+    ]);
+    let query = Array1::from_vec(vec![0.95, 0.31]);
+
+    let ranked = engine.top_k(&query, 2).unwrap();
+    assert_eq!(ranked.len(), 2);
+    assert_eq!(ranked[0].concept.name, "Strict");
+    assert!(!ranked[0].passed_threshold && ranked[0].margin < 0.0);
+    assert!(ranked[1].passed_threshold);
+    assert!((ranked[1].margin - (ranked[1].similarity - 0.5)).abs() < 1e-6);
+
+    let explanation = engine.explain(&query, 1).unwrap();
+    let winner = explanation.winner.as_ref().unwrap();
+    assert_eq!(winner.concept.name, engine.find_best_match(&query).unwrap().name);
+    assert!((explanation.lead.unwrap() - (winner.similarity - ranked[0].similarity)).abs() < 1e-6);
+    assert_eq!(explanation.candidates.len(), 1);
+    assert!(explanation.to_string().contains("Winner: Loose"));
+}
+
+#[test]
+fn test_concepts_can_override_the_engine_metric() {
+    use starweave_mvp::concepts::SimilarityMetric;
+
+    let query = Array1::from_vec(vec![2.0, 0.0]);
+    let near = Array1::from_vec(vec![1.0, 0.0]);
+    assert!((SimilarityMetric::Cosine.similarity(&near, &query, None) - 1.0).abs() < 1e-6);
+    assert!((SimilarityMetric::Dot.similarity(&near, &query, None) - 2.0).abs() < 1e-6);
+    assert!((SimilarityMetric::NegEuclidean.similarity(&near, &query, None) + 1.0).abs() < 1e-6);
+    assert!((SimilarityMetric::Angular.similarity(&Array1::from_vec(vec![0.0, 1.0]), &query, None) - 0.5).abs() < 1e-6);
+    let variance = Array1::from_vec(vec![4.0, 1.0]);
+    assert!((SimilarityMetric::Mahalanobis.similarity(&near, &query, Some(&variance)) + 0.5).abs() < 1e-6);
+
+    // Zero or non-finite variances are refused on every path, not just in packs
+    let mut engine = SimilarityEngine::from_concepts(vec![concept("Spread", vec![1.0, 0.0], 0.5)]);
+    let id = engine.id_of("Spread").unwrap();
+    for bad in [vec![0.0, 0.0], vec![1.0, f32::NAN]] {
+        let edit = engine.edit_concept(id, |c| c.covariance = Some(Array1::from_vec(bad)));
+        assert!(matches!(edit, Err(SimilarityError::InvalidOperation { .. })));
+    }
+    let flat = ConceptVector { covariance: Some(Array1::from_vec(vec![0.0, 1.0])), ..concept("Flat", vec![0.0, 1.0], 0.5) };
+    assert!(engine.add_concept(flat).is_err());
+    assert_eq!("neg-euclidean".parse::<SimilarityMetric>(), Ok(SimilarityMetric::NegEuclidean));
+
+    // Cosine calls both concepts identical to the query; the distance-based one sees the gap
+    let mut distance = concept("Distance", vec![1.0, 0.0], -0.5);
+    distance.metric = Some(SimilarityMetric::NegEuclidean);
+    let engine = SimilarityEngine::from_concepts(vec![concept("Direction", vec![1.0, 0.0], 0.9), distance]);
+
+    let ranked = engine.top_k(&query, 2).unwrap();
+    assert_eq!(ranked[0].concept.name, "Direction");
+    assert!(ranked[0].passed_threshold);
+    assert_eq!(ranked[1].metric, SimilarityMetric::NegEuclidean);
+    assert!(!ranked[1].passed_threshold);
+
+    let scores = engine.score_batch(&query.clone().insert_axis(ndarray::Axis(0))).unwrap();
+    assert!((scores[[0, 1]] + 1.0).abs() < 1e-6);
+
+    let engine = engine.with_metric(SimilarityMetric::Dot);
+    assert!((engine.top_k(&query, 1).unwrap()[0].similarity - 2.0).abs() < 1e-6);
+
+    // Raw scores in different units don't compare: the clearer margin over its threshold wins
+    let mut close = concept("Close", vec![1.2, 0.0], -1.0); // -0.2, well inside -1.0
+    close.metric = Some(SimilarityMetric::NegEuclidean);
+    let aligned = concept("Aligned", vec![0.9, 0.4359], 0.5); // Cosine 0.9
+    let engine = SimilarityEngine::from_concepts(vec![close, aligned]);
+    let query = Array1::from_vec(vec![1.0, 0.0]);
+    assert_eq!(engine.find_best_match(&query).unwrap().name, "Close");
+    assert_eq!(engine.top_k(&query, 2).unwrap()[0].concept.name, "Close");
+    assert_eq!(engine.try_find_hierarchical_match(&query).unwrap().unwrap().concept.name, "Close");
+}
+
+#[test]
+fn test_prototype_aggregation_policies() {
+    use starweave_mvp::concepts::PrototypeAggregation;
+
+    let scores = [0.9, 0.1];
+    assert_eq!(PrototypeAggregation::Max.aggregate(&scores), (0.9, 0));
+    assert!((PrototypeAggregation::Mean.aggregate(&scores).0 - 0.5).abs() < 1e-6);
+    let soft = PrototypeAggregation::SoftMax { temperature: 0.1 }.aggregate(&scores).0;
+    assert!(soft > 0.85 && soft < 0.9);
+    let warm = PrototypeAggregation::SoftMax { temperature: 100.0 }.aggregate(&scores).0;
+    assert!((warm - 0.5).abs() < 0.01);
+
+    // Mean over a far-apart pair no longer clears a threshold that Max does
+    let exemplars = vec![Array1::from_vec(vec![1.0, 0.0]), Array1::from_vec(vec![0.0, 1.0])];
+    let broad = concept("Broad", vec![0.0, 0.0], 0.8).with_prototypes(exemplars.clone(), PrototypeAggregation::Max);
+    let strict = concept("Strict", vec![0.0, 0.0], 0.8).with_prototypes(exemplars, PrototypeAggregation::Mean);
+    let engine = SimilarityEngine::from_concepts(vec![broad, strict]);
+    let query = Array1::from_vec(vec![0.0, 1.0]);
+    assert_eq!(engine.find_best_match(&query).unwrap().name, "Broad");
+    assert_eq!(engine.match_batch(&query.insert_axis(ndarray::Axis(0))).unwrap()[0].unwrap().concept, 0);
+}
+
+#[test]
+fn test_confirmed_matches_nudge_concepts_within_max_drift() {
+    use starweave_mvp::concepts::{LearningConfig, PrototypeAggregation};
+
+    let original = vec![1.0, 0.0];
+    let mut engine = SimilarityEngine::from_concepts(vec![concept("Focus", original.clone(), 0.5)]);
+    let input = Array1::from_vec(vec![0.0, 1.0]);
+    assert_eq!(engine.confirm_match("Focus", &input), Ok(None));
+    assert!(engine.learning_stats().is_empty());
+
+    let config = LearningConfig { learning_rate: 0.2, momentum: 0.5, max_drift: 0.1 };
+    let mut engine = engine.with_learning(config).unwrap();
+    let first = engine.confirm_match("Focus", &input).unwrap().unwrap();
+    assert!(first > 0.0 && engine.concepts()[0].vector[1] > 0.0);
+
+    for _ in 0..50 {
+        engine.confirm_match("Focus", &input).unwrap();
+    }
+    let stats = &engine.learning_stats()[0];
+    assert_eq!(stats.updates, 51);
+    assert!(stats.drift <= config.max_drift + 1e-4, "drift {} exceeded the limit", stats.drift);
+    assert!(stats.drift > first);
+
+    // A saved pack keeps the original, so drift doesn't compound across reloads
+    let mut reloaded = SimilarityEngine::from_pack(&engine.to_pack(), None).unwrap().with_learning(config).unwrap();
+    assert_eq!(reloaded.learning_stats(), engine.learning_stats());
+    for _ in 0..50 {
+        reloaded.confirm_match("Focus", &input).unwrap();
+    }
+    assert!(reloaded.learning_stats()[0].drift <= config.max_drift + 1e-4);
+
+    engine.reset_concept("Focus").unwrap();
+    assert_eq!(engine.concepts()[0].vector.to_vec(), original);
+    assert!(engine.learning_stats().is_empty());
+    assert!(matches!(engine.confirm_match("Missing", &input), Err(SimilarityError::UnknownConcept { .. })));
+
+    let broad = concept("Broad", vec![0.0, 0.0], 0.5).with_prototypes(vec![Array1::from_vec(vec![1.0, 0.0])], PrototypeAggregation::Max);
+    let mut engine = SimilarityEngine::from_concepts(vec![broad]).with_learning(config).unwrap();
+    assert!(matches!(engine.confirm_match("Broad", &input), Err(SimilarityError::InvalidOperation { .. })));
+
+    // Settings that would write NaN into a concept are refused up front
+    for bad in [
+        LearningConfig { learning_rate: f32::NAN, ..config },
+        LearningConfig { learning_rate: f32::INFINITY, ..config },
+        LearningConfig { learning_rate: 0.0, ..config },
+        LearningConfig { momentum: 1.0, ..config },
+        LearningConfig { max_drift: f32::INFINITY, ..config },
+        LearningConfig { max_drift: -0.1, ..config },
+    ] {
+        assert!(matches!(engine.set_learning(Some(bad)), Err(SimilarityError::InvalidConfig { .. })));
+    }
+    assert_eq!(engine.learning(), Some(config));
+}
+
+#[test]
+fn test_threshold_jitter_follows_state_and_can_be_disabled() {
+    use starweave_mvp::concepts::ThresholdJitter;
+
+    let jitter = ThresholdJitter { amplitude: 0.1 };
+    assert!((jitter.offset([1.0, 0.0], 0.0) + 0.05).abs() < 1e-6); // Exploratory lowers the bar
+    assert!((jitter.offset([0.0, 1.0], 1.0) - 0.1).abs() < 1e-6);  // Bounded by the amplitude
+
+    // Similarity 0.6 sits inside the 0.6 ± 0.1 band, so jittered matching sometimes fires
+    let mut borderline = concept("Borderline", vec![1.0, 0.0], 0.6);
+    borderline.stochastic_state = [0.5, 0.5];
+    borderline.jitter = Some(jitter);
+    let query = Array1::from_vec(vec![0.6, 0.8]);
+    let outcomes = |engine: &SimilarityEngine| (0..200).map(|_| engine.find_best_match(&query).is_some()).collect::<Vec<_>>();
+
+    let engine = SimilarityEngine::from_concepts(vec![borderline.clone()]).with_jitter_seed(7);
+    let first = outcomes(&engine);
+    assert!(first.contains(&true) && first.contains(&false));
+    let replay = SimilarityEngine::from_concepts(vec![borderline.clone()]).with_jitter_seed(7);
+    assert_eq!(outcomes(&replay), first);
+
+    // One explanation holds the winner, the listed thresholds and the taxonomy walk to a single draw
+    let child = ConceptVector { parent: Some("Borderline".to_string()), ..concept("Leaf", vec![0.0, 1.0], 0.99) };
+    let taxonomy = SimilarityEngine::from_concepts(vec![borderline.clone(), child]).with_jitter_seed(3);
+    for _ in 0..100 {
+        let explanation = taxonomy.explain(&query, 2).unwrap();
+        let listed = explanation.candidates.iter().find(|c| c.concept.name == "Borderline").unwrap();
+        assert_eq!(explanation.winner.is_some(), listed.passed_threshold);
+        assert_eq!(explanation.refined.is_some(), listed.passed_threshold);
+    }
+
+    let mut fixed = SimilarityEngine::from_concepts(vec![borderline]);
+    fixed.set_jitter_enabled(false);
+    assert!(outcomes(&fixed).iter().all(|matched| !matched));
+    assert_eq!(fixed.top_k(&query, 1).unwrap()[0].threshold, 0.6);
+}
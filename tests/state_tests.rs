@@ -17,6 +17,7 @@ fn test_state_evolution() {
         threshold: 0.6,
         last_interaction_time: now - 3600, // 1 hour ago
         curiosity_score: 0.8,
+        ..ConceptVector::default()
     };
 
     let updater = StateUpdater::new();